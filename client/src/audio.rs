@@ -113,6 +113,44 @@ impl AudioPlayer {
             StaticSoundData::from_file("resources/audio/attach.ogg", 
             StaticSoundSettings::default()).unwrap());
 
+        player.source_map.insert("headshot".to_string(),
+            StaticSoundData::from_file("resources/audio/hit.ogg", 
+            StaticSoundSettings::default().playback_rate(1.6)).unwrap());
+
+        player.source_map.insert("dash".to_string(),
+            StaticSoundData::from_file("resources/audio/release.ogg", 
+            StaticSoundSettings::default().volume(0.8)).unwrap());
+
+        player.source_map.insert("roped".to_string(),
+            StaticSoundData::from_file("resources/audio/bell.ogg", 
+            StaticSoundSettings::default()).unwrap());
+
+        player.source_map.insert("break".to_string(),
+            StaticSoundData::from_file("resources/audio/hit.ogg", 
+            StaticSoundSettings::default().playback_rate(0.5)).unwrap());
+
+        // capture the flag announcer cues
+        player.source_map.insert("flag_taken".to_string(),
+            StaticSoundData::from_file("resources/audio/attach.ogg", 
+            StaticSoundSettings::default().playback_rate(1.3)).unwrap());
+
+        player.source_map.insert("flag_dropped".to_string(),
+            StaticSoundData::from_file("resources/audio/release.ogg", 
+            StaticSoundSettings::default().playback_rate(0.7)).unwrap());
+
+        player.source_map.insert("flag_returned".to_string(),
+            StaticSoundData::from_file("resources/audio/reload.ogg", 
+            StaticSoundSettings::default()).unwrap());
+
+        player.source_map.insert("flag_captured".to_string(),
+            StaticSoundData::from_file("resources/audio/bell2.ogg", 
+            StaticSoundSettings::default().playback_rate(1.25)).unwrap());
+
+        // world event announcer cue
+        player.source_map.insert("announcement".to_string(),
+            StaticSoundData::from_file("resources/audio/bell2.ogg", 
+            StaticSoundSettings::default().playback_rate(0.8)).unwrap());
+
         Some(player)
     }
 
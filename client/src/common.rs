@@ -31,6 +31,16 @@ pub fn update_shader_camera(camera: &Camera, shader: &Shader, width: u32, height
     }
 }
 
+/**
+ * Name to keep match history under: the first command line argument, or the OS user name
+ */
+pub fn player_name() -> String {
+    std::env::args().nth(1)
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or("player".to_string())
+}
+
 pub fn process_events_lobby(
     events: &Receiver<(f64, glfw::WindowEvent)>
 ) {
@@ -119,12 +129,28 @@ pub fn process_inputs_lobby(
     window: &mut glfw::Window,
     ready_sent: &mut bool,
     first_enter: &mut bool,
+    team_pressed: &mut bool,
+    current_team: Option<u8>,
+    num_teams: u8,
     stream: &mut TcpStream
 ) {
+    // cycle through teams, the server decides whether the switch is allowed
+    if !*team_pressed && num_teams > 0 && window.get_key(Key::T) == Action::Press {
+        *team_pressed = true;
+        let next_team = match current_team {
+            Some(team) => (team + 1) % num_teams,
+            None => 0,
+        };
+        let team_bitcode = ReadyECS{ready:*ready_sent, team:Some(next_team), name:None};
+        write_data(stream, bitcode::serialize(&team_bitcode).unwrap());
+    }
+    if window.get_key(Key::T) == Action::Release {
+        *team_pressed = false;
+    }
     if !*first_enter && !*ready_sent && window.get_key(Key::Enter) == Action::Press {
         *ready_sent = true;
         // send ready JSON (hardcoded for now)
-        let ready_bitcode = ReadyECS{ready:true};
+        let ready_bitcode = ReadyECS{ready:true, team:None, name:None};
         write_data(stream, bitcode::serialize(&ready_bitcode).unwrap());
     }
     if window.get_key(Key::Enter) == Action::Release {
@@ -173,6 +199,25 @@ pub fn process_inputs_game(
     if window.get_key(Key::R) == Action::Press {
         input_component.r_pressed = true;
     }
+    // reel the lasso in and out
+    if window.get_key(Key::Q) == Action::Press {
+        input_component.q_pressed = true;
+    }
+    if window.get_key(Key::E) == Action::Press {
+        input_component.e_pressed = true;
+    }
+    // fling a lassoed prop
+    if window.get_key(Key::F) == Action::Press {
+        input_component.f_pressed = true;
+    }
+    // dash
+    if window.get_key(Key::V) == Action::Press {
+        input_component.v_pressed = true;
+    }
+    // brake
+    if window.get_key(Key::X) == Action::Press {
+        input_component.x_pressed = true;
+    }
     if window.get_key(Key::Space) == Action::Press {
         *roll = true;
     }
@@ -1,12 +1,26 @@
 use crate::camera::Camera;
 use crate::model::Model;
 use crate::shader::Shader;
-use cgmath::{Deg, InnerSpace, Matrix4, perspective, SquareMatrix, Vector2, Vector3};
+use cgmath::{Deg, InnerSpace, Matrix4, perspective, Vector2, Vector3, vec3};
 use std::ffi::{CStr};
 use cgmath::num_traits::{abs, clamp};
 
+// radius of the force field model
+const MODEL_RADIUS: f32 = 250.0;
+// bubble drawn around spawn protected players
+const SHIELD_RADIUS: f32 = 1.4;
+const SHIELD_ALPHA: f32 = 0.6;
+// king of the hill zones, hills out of play are barely visible
+const HILL_ALPHA: f32 = 0.5;
+const INACTIVE_HILL_ALPHA: f32 = 0.1;
+// gravity wells, repulsors and wind from the world file, a faint hint of where they reach
+const FORCE_SOURCE_ALPHA: f32 = 0.05;
+// nebulae from world events, thick enough to hide what is inside
+const NEBULA_ALPHA: f32 = 0.85;
+
 pub struct ForceField {
     pub radius: f32,
+    pub center: Vector3<f32>,
     model: Model,
     shader: Shader,
     screen_size: Vector2<f32>,
@@ -18,6 +32,7 @@ impl ForceField {
         let shader = Shader::new("shaders/forcefield.vs", "shaders/forcefield.fs");
         let force_field = ForceField {
             radius,
+            center: vec3(0.0, 0.0, 0.0),
             model,
             shader,
             screen_size,
@@ -26,26 +41,14 @@ impl ForceField {
     }
 
     pub unsafe fn draw(&self, camera: &Camera, player_pos: Vector3<f32>) {
-        self.shader.use_program();
+        self.use_camera(camera);
 
-        unsafe {
-            let view = camera.GetViewMatrix();
-            self.shader.set_mat4(c_str!("view"), &view);
-            let projection: Matrix4<f32> = perspective(
-                Deg(camera.Fov),
-                self.screen_size.x / self.screen_size.y,
-                0.1,
-                10000.0,
-            );
-            self.shader.set_mat4(c_str!("projection"), &projection);
-        }
-
-        let model = Matrix4::identity();
+        let model = Matrix4::from_translation(self.center) * Matrix4::from_scale(self.radius / MODEL_RADIUS);
         self.shader.set_mat4(c_str!("model"), &model);
 
         // alpha will be 0 at inner radius
         let show_radius = 100.0;
-        let distance = abs(player_pos.magnitude() - self.radius);
+        let distance = abs((player_pos - self.center).magnitude() - self.radius);
         let mut alpha = distance / show_radius;
         alpha = clamp(alpha, 0.0, 1.0).powi(2);
         alpha = 1.0 - alpha;
@@ -53,4 +56,70 @@ impl ForceField {
 
         self.model.draw(&self.shader);
     }
+
+    /**
+     * Draw a small force field bubble around a spawn protected player
+     */
+    pub unsafe fn draw_shield(&self, camera: &Camera, center: Vector3<f32>) {
+        self.use_camera(camera);
+
+        let model = Matrix4::from_translation(center) * Matrix4::from_scale(SHIELD_RADIUS / MODEL_RADIUS);
+        self.shader.set_mat4(c_str!("model"), &model);
+        self.shader.set_float(c_str!("alpha"), SHIELD_ALPHA);
+
+        self.model.draw(&self.shader);
+    }
+
+    /**
+     * Draw a king of the hill zone
+     */
+    pub unsafe fn draw_hill(&self, camera: &Camera, center: Vector3<f32>, radius: f32, active: bool) {
+        self.use_camera(camera);
+
+        let model = Matrix4::from_translation(center) * Matrix4::from_scale(radius / MODEL_RADIUS);
+        self.shader.set_mat4(c_str!("model"), &model);
+        self.shader.set_float(c_str!("alpha"), if active { HILL_ALPHA } else { INACTIVE_HILL_ALPHA });
+
+        self.model.draw(&self.shader);
+    }
+
+    /**
+     * Draw the reach of a force source
+     */
+    pub unsafe fn draw_force_source(&self, camera: &Camera, center: Vector3<f32>, radius: f32) {
+        self.use_camera(camera);
+
+        let model = Matrix4::from_translation(center) * Matrix4::from_scale(radius / MODEL_RADIUS);
+        self.shader.set_mat4(c_str!("model"), &model);
+        self.shader.set_float(c_str!("alpha"), FORCE_SOURCE_ALPHA);
+
+        self.model.draw(&self.shader);
+    }
+
+    /**
+     * Draw a nebula cloud
+     */
+    pub unsafe fn draw_nebula(&self, camera: &Camera, center: Vector3<f32>, radius: f32) {
+        self.use_camera(camera);
+
+        let model = Matrix4::from_translation(center) * Matrix4::from_scale(radius / MODEL_RADIUS);
+        self.shader.set_mat4(c_str!("model"), &model);
+        self.shader.set_float(c_str!("alpha"), NEBULA_ALPHA);
+
+        self.model.draw(&self.shader);
+    }
+
+    unsafe fn use_camera(&self, camera: &Camera) {
+        self.shader.use_program();
+
+        let view = camera.GetViewMatrix();
+        self.shader.set_mat4(c_str!("view"), &view);
+        let projection: Matrix4<f32> = perspective(
+            Deg(camera.Fov),
+            self.screen_size.x / self.screen_size.y,
+            0.1,
+            10000.0,
+        );
+        self.shader.set_mat4(c_str!("projection"), &projection);
+    }
 }
@@ -100,6 +100,7 @@ fn main() -> io::Result<()> {
     let mut fullscreen = false;
     let mut f11_pressed = false;
     let mut mmb_clicked = false;
+    let mut team_pressed = false;
 
     // glfw: initialize and configure
     // ------------------------------
@@ -167,7 +168,7 @@ fn main() -> io::Result<()> {
 
     // set up ui
     let mut ui_elems = ui::UI::initialize(screen_size, sprite_shader.id, width as f32, height as f32);
-    let mut rankings = Vec::new();
+    let mut round_results: Option<RoundResults> = None;
 
     // render splash screen
     unsafe { gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT) };
@@ -189,6 +190,8 @@ fn main() -> io::Result<()> {
         vec4(252.0 / 255.0, 201.0 / 255.0, 0.0 / 255.0, 1.0),
         vec4(88.0 / 255.0, 180.0 / 255.0, 36.0 / 255.0, 1.0),
     ];
+    let friend_tracker_color = vec4(1.0, 1.0, 1.0, 1.0);
+    let foe_tracker_color = vec4(1.0, 0.2, 0.2, 1.0);
     let mut tracker = unsafe {
         let tracker = Tracker::new(sprite_shader.id, 0.9, vec2(width as f32, height as f32));
         tracker
@@ -203,7 +206,7 @@ fn main() -> io::Result<()> {
     ], screen_size);
 
     // create force field
-    let force_field = ForceField::new(250.0, screen_size);
+    let mut force_field = ForceField::new(250.0, screen_size);
 
     // create lasso
     let lasso = Lasso::new();
@@ -238,6 +241,28 @@ fn main() -> io::Result<()> {
         particles_per_100ms: 2
     });
 
+    emitter_specifiers.insert("dash_burst".to_string(), ParticleEmitterSpecifier{
+        stl_min: 0.2, stl_max: 0.4,
+        scl_min: 0.05, scl_max: 0.15,
+        phi_max: PI / 8.,
+        col_start: vec4(1., 1., 1., 1.),
+        col_end: vec4(0.4, 0.7, 1., 1.),
+        particle_limit: 40,
+        secs_to_live: 0.15,
+        particles_per_100ms: 20
+    });
+
+    emitter_specifiers.insert("break_dust".to_string(), ParticleEmitterSpecifier{
+        stl_min: 1.0, stl_max: 3.0,
+        scl_min: 0.1, scl_max: 0.3,
+        phi_max: PI / 2.,
+        col_start: vec4(0.6, 0.5, 0.4, 1.),
+        col_end: vec4(0.3, 0.25, 0.2, 1.),
+        particle_limit: 40,
+        secs_to_live: 0.3,
+        particles_per_100ms: 15
+    });
+
     // client ECS to be sent to server
     let mut client_ecs: Option<ClientECS> = None;
 
@@ -295,6 +320,10 @@ fn main() -> io::Result<()> {
         .set_nonblocking(true)
         .expect("Failed to set stream as nonblocking");
 
+    // introduce ourselves so the server can look up our match history
+    let name_bitcode = ReadyECS{ready:false, team:None, name:Some(player_name())};
+    write_data(&mut stream, bitcode::serialize(&name_bitcode).unwrap());
+
     let mut curr_id = client_id;
 
     let mut frame_count = 0;
@@ -321,7 +350,7 @@ fn main() -> io::Result<()> {
 
         match game_state {
             GameState::EnteringLobby => {
-                rankings.clear();
+                round_results = None;
                 ready_sent = false; // prevents sending ready message twice
                 zoomed = false;
                 mmb_clicked = false;
@@ -334,10 +363,14 @@ fn main() -> io::Result<()> {
                 
             }
             GameState::InLobby => {
+                let current_team = lobby_ecs.ids.get(client_id).and_then(|&id| lobby_ecs.team_components.get(id).copied());
                 process_inputs_lobby(
                     &mut window,
                     &mut ready_sent,
                     &mut first_enter,
+                    &mut team_pressed,
+                    current_team,
+                    lobby_ecs.num_teams,
                     &mut stream
                 );
 
@@ -349,7 +382,9 @@ fn main() -> io::Result<()> {
                     if lobby_ecs.ids.len() > 0 {
                         curr_id = lobby_ecs.players.iter().position(|&r| r == lobby_ecs.ids[client_id]).unwrap();
                         gl::DepthMask(gl::FALSE);
-                        ui_elems.draw_lobby(&mut lobby_ecs, curr_id);
+                        // hold tab to see the server's leaderboard
+                        let show_leaderboard = window.get_key(Key::Tab) == Action::Press;
+                        ui_elems.draw_lobby(&mut lobby_ecs, curr_id, show_leaderboard);
                         gl::DepthMask(gl::TRUE);
                     }
                 }
@@ -403,6 +438,11 @@ fn main() -> io::Result<()> {
                 let mut roll = false;
 
                 let mut player_vel = vec3(0.0, 0.0, 0.0);
+                let mut player_dampening = false;
+                let mut shielded_players = Vec::<Vector3<f32>>::new();
+                let mut hills = Vec::<HillComponent>::new();
+                let mut force_sources = Vec::<ForceSourceComponent>::new();
+                let mut nebulae = Vec::<NebulaComponent>::new();
 
                 process_inputs_game(
                     &mut window,
@@ -470,7 +510,12 @@ fn main() -> io::Result<()> {
                             // // TODO: handle this throwing an error. Occasionally crashes ^
                             let value: ClientECS = bitcode::deserialize(&read_buf[4..])
                                 .expect("Error converting string to ClientECS");
+                            let game_ended = value.game_ended;
                             client_ecs = Some(value);
+                            // anything after the last game state is the round results
+                            if game_ended {
+                                break;
+                            }
                         }
                         Ok(_) => {
                             break;
@@ -542,13 +587,24 @@ fn main() -> io::Result<()> {
                                     //     &emitter_specifiers["fire_spark"]
                                     // ));
                                 },
-                                EventType::HitEvent { player, target , hit_x, hit_y, hit_z} => {
+                                EventType::HitEvent { player, target, region, hit_x, hit_y, hit_z} => {
+                                    let headshot = region == HitRegion::Head;
                                     if target == player_key && c_ecs.health_components[player_key].alive {
-                                        camera.ScreenShake.add_trauma(0.5);
+                                        camera.ScreenShake.add_trauma(if headshot { 0.8 } else { 0.5 });
                                         screenshake_event = true;
                                         ui_elems.damage.add_alpha(0.6);
                                     } else if player == player_key && c_ecs.players.contains(&target) && c_ecs.health_components[target].alive {
-                                        ui_elems.hitmarker.add_alpha(1.0);
+                                        if headshot {
+                                            ui_elems.headshot_hitmarker.add_alpha(1.0);
+                                        } else {
+                                            ui_elems.hitmarker.add_alpha(1.0);
+                                        }
+                                    }
+                                    if headshot && player == player_key && audio_enabled {
+                                        match audio.as_mut().unwrap().play_static(&"headshot".to_string()) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
                                     }
                                     let particle_component = &c_ecs.particle_components[event];
                                     particle_emitters.push(ParticleEmitter::new(
@@ -582,13 +638,14 @@ fn main() -> io::Result<()> {
                                         };
                                     }
                                 },
-                                EventType::DeathEvent { player, killer } => {
+                                EventType::DeathEvent { player, killer, cause, ref assists } => {
                                     let k_id = c_ecs.players.iter().position(|&x| x == killer).unwrap();
                                     let p_id = c_ecs.players.iter().position(|&x| x == player).unwrap();
+                                    let assist_ids = assists.iter().filter_map(|&assist| c_ecs.players.iter().position(|&x| x == assist)).collect();
 
-                                    ui_elems.display_death_message(k_id, p_id);
+                                    // killer is the player themselves when the environment got them
+                                    ui_elems.display_death_message(k_id, p_id, cause, assist_ids);
                                     
-                                    rankings.push(c_ecs.players.iter().position(|&x| x == player).unwrap());
                                     if player == player_key {
                                         camera.ScreenShake.add_trauma(1.0);
                                         ui_elems.damage.add_alpha(1.0);
@@ -604,8 +661,48 @@ fn main() -> io::Result<()> {
                                         };
                                     }
                                 }, 
-                                EventType::DisconnectEvent { player } => {
-                                    rankings.push(c_ecs.players.iter().position(|&x| x == player).unwrap());
+                                EventType::DisconnectEvent { .. } => (),
+                                EventType::ImpactEvent { player, x, y, z, magnitude } => {
+                                    if player == player_key {
+                                        camera.ScreenShake.add_trauma((magnitude / 40.0).min(1.0));
+                                    }
+                                    // sparks fly away from the player's center
+                                    let player_pos = &c_ecs.position_components[player];
+                                    let normal = vec3(x - player_pos.x, y - player_pos.y, z - player_pos.z);
+                                    let normal = if normal.magnitude() > 0.0 { normal.normalize() } else { vec3(0.0, 1.0, 0.0) };
+                                    let vel = &c_ecs.velocity_components[player];
+                                    particle_emitters.push(ParticleEmitter::new(
+                                        vec3(x, y, z),
+                                        normal,
+                                        vec3(vel.vel_x, vel.vel_y, vel.vel_z),
+                                        &emitter_specifiers["hit_spark"]
+                                    ));
+                                    if audio_enabled {
+                                        match audio.as_mut().unwrap().play_sound(&"hit".to_string(), x, y, z, Some(player)) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
+                                EventType::PropHitEvent { player, target, hit_x, hit_y, hit_z } => {
+                                    if target == player_key {
+                                        camera.ScreenShake.add_trauma(0.5);
+                                        ui_elems.damage.add_alpha(0.6);
+                                    } else if player == player_key {
+                                        ui_elems.hitmarker.add_alpha(1.0);
+                                    }
+                                    if audio_enabled {
+                                        match audio.as_mut().unwrap().play_sound(&"hit".to_string(), hit_x, hit_y, hit_z, Some(target)) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
+                                EventType::ZoneDamageEvent { player } => {
+                                    if player == player_key {
+                                        camera.ScreenShake.add_trauma(0.5);
+                                        ui_elems.damage.add_alpha(1.0);
+                                    }
                                 },
                                 EventType::StartMoveEvent { player } => {
                                     if audio_enabled {
@@ -621,6 +718,26 @@ fn main() -> io::Result<()> {
                                         audio.as_mut().unwrap().stop_thruster(player);
                                     };
                                 },
+                                EventType::DashEvent { player, dir_x, dir_y, dir_z } => {
+                                    if player == player_key {
+                                        camera.ScreenShake.add_trauma(0.3);
+                                    }
+                                    // exhaust bursts out behind the dash
+                                    let player_pos = &c_ecs.position_components[player];
+                                    let vel = &c_ecs.velocity_components[player];
+                                    particle_emitters.push(ParticleEmitter::new(
+                                        vec3(player_pos.x, player_pos.y, player_pos.z),
+                                        vec3(-dir_x, -dir_y, -dir_z),
+                                        vec3(vel.vel_x, vel.vel_y, vel.vel_z),
+                                        &emitter_specifiers["dash_burst"]
+                                    ));
+                                    if audio_enabled {
+                                        match audio.as_mut().unwrap().play_sound(&"dash".to_string(), player_pos.x, player_pos.y, player_pos.z, Some(player)) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
                                 EventType::LassoThrowEvent { player } => {
                                     if audio_enabled {
                                         let player_pos = &c_ecs.position_components[player];
@@ -630,9 +747,75 @@ fn main() -> io::Result<()> {
                                         };
                                     }
                                 },
-                                EventType::LassoAttachEvent { target, hit_x, hit_y, hit_z } => {
+                                EventType::LassoAttachEvent { target, target_type, hit_x, hit_y, hit_z, .. } => {
+                                    let roped = target_type == LassoTarget::Player && target == player_key;
+                                    if roped {
+                                        camera.ScreenShake.add_trauma(0.5);
+                                    }
+                                    if audio_enabled {
+                                        let result = if roped {
+                                            audio.as_mut().unwrap().play_static(&"roped".to_string()).map(|_| ())
+                                        } else {
+                                            audio.as_mut().unwrap().play_sound(&"attach".to_string(), hit_x, hit_y, hit_z, Some(target))
+                                        };
+                                        match result {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
+                                EventType::FlagTakenEvent { .. } => {
+                                    if audio_enabled {
+                                        match audio.as_mut().unwrap().play_static(&"flag_taken".to_string()) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
+                                EventType::FlagDropEvent { .. } => {
                                     if audio_enabled {
-                                        match audio.as_mut().unwrap().play_sound(&"attach".to_string(), hit_x, hit_y, hit_z, Some(target)) {
+                                        match audio.as_mut().unwrap().play_static(&"flag_dropped".to_string()) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
+                                EventType::FlagReturnEvent { .. } => {
+                                    if audio_enabled {
+                                        match audio.as_mut().unwrap().play_static(&"flag_returned".to_string()) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
+                                EventType::FlagCaptureEvent { player, .. } => {
+                                    if player == player_key {
+                                        camera.ScreenShake.add_trauma(0.5);
+                                    }
+                                    if audio_enabled {
+                                        match audio.as_mut().unwrap().play_static(&"flag_captured".to_string()) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
+                                EventType::BreakEvent { player, x, y, z, radius } => {
+                                    if player == player_key {
+                                        ui_elems.hitmarker.add_alpha(1.0);
+                                    }
+                                    // a burst of dust in every direction, bigger props throw out more
+                                    let particle_component = &c_ecs.particle_components[event];
+                                    let vel = vec3(particle_component.vel_x, particle_component.vel_y, particle_component.vel_z);
+                                    for normal in [vec3(1.0, 0.0, 0.0), vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), vec3(0.0, -1.0, 0.0), vec3(0.0, 0.0, 1.0), vec3(0.0, 0.0, -1.0)] {
+                                        particle_emitters.push(ParticleEmitter::new(
+                                            vec3(x, y, z) + normal * radius * 0.5,
+                                            normal,
+                                            vel,
+                                            &emitter_specifiers["break_dust"]
+                                        ));
+                                    }
+                                    if audio_enabled {
+                                        match audio.as_mut().unwrap().play_sound(&"break".to_string(), x, y, z, None) {
                                             Ok(_) => (),
                                             Err(e) => eprintln!("Audio error playing sound: {e}"),
                                         };
@@ -646,6 +829,27 @@ fn main() -> io::Result<()> {
                                             Err(e) => eprintln!("Audio error playing sound: {e}"),
                                         };
                                     }
+                                },
+                                EventType::AnnouncementEvent { kind, .. } => {
+                                    ui_elems.announce(kind);
+                                    if audio_enabled {
+                                        match audio.as_mut().unwrap().play_static(&"announcement".to_string()) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
+                                },
+                                EventType::SupplyPickupEvent { player } => {
+                                    if player == player_key {
+                                        arm.reload();
+                                    }
+                                    if audio_enabled {
+                                        let player_pos = &c_ecs.position_components[player];
+                                        match audio.as_mut().unwrap().play_sound(&"reload".to_string(), player_pos.x, player_pos.y, player_pos.z, Some(player)) {
+                                            Ok(_) => (),
+                                            Err(e) => eprintln!("Audio error playing sound: {e}"),
+                                        };
+                                    }
                                 }
                             }
                         }
@@ -653,6 +857,7 @@ fn main() -> io::Result<()> {
                         // player velocity
                         let velocity = &c_ecs.velocity_components[player_key];
                         player_vel = vec3(velocity.vel_x, velocity.vel_y, velocity.vel_z);
+                        player_dampening = c_ecs.dampening.get(player_key).copied().unwrap_or(false);
                         if !screenshake_event {
                             // kinetic energy should be more realistic, but feels wrong
                             // let delta_ke = (0.5 * velocity.mass * (player_vel.magnitude().powi(2) - vel_prev.magnitude().powi(2))).abs();
@@ -741,6 +946,12 @@ fn main() -> io::Result<()> {
                                 _ => ()
                             }
 
+                            // player was respawned by the game mode
+                            if client_health.alive && show_death_screen {
+                                spectator_mode = false;
+                                show_death_screen = false;
+                            }
+
                             if !client_health.alive && input_component.enter_pressed {
                                 spectator_mode = true;
                                 show_death_screen = false;
@@ -836,19 +1047,48 @@ fn main() -> io::Result<()> {
                                     );
                                 }
 
+                                if player != player_key && c_ecs.health_components[player].alive && c_ecs.health_components[player].shield_ticks > 0 {
+                                    let pos = &c_ecs.position_components[player];
+                                    shielded_players.push(vec3(pos.x, pos.y, pos.z));
+                                }
+
                                 // draw trackers
                                 if player != player_key && c_ecs.health_components[player].alive {
                                     let pos = &c_ecs.position_components[player];
                                     let pos = vec3(pos.x, pos.y, pos.z);
+                                    // in team modes trackers show allegiance instead of player colour
+                                    let color = match (c_ecs.team_components.get(player), c_ecs.team_components.get(player_key)) {
+                                        (Some(team), Some(my_team)) if team == my_team => friend_tracker_color,
+                                        (Some(_), Some(_)) => foe_tracker_color,
+                                        _ => tracker_colors[i % tracker_colors.len()],
+                                    };
                                     tracker.draw_tracker(
                                         &camera,
                                         pos,
-                                        tracker_colors[i % tracker_colors.len()],
+                                        color,
                                         &mut trackers,
                                     );
                                 }
                                 i += 1;
                             }
+                            // flags get trackers too, unless we are the one carrying them
+                            for (flag, flag_component) in &c_ecs.flag_components {
+                                if flag_component.carrier == Some(player_key) {
+                                    continue;
+                                }
+                                let pos = &c_ecs.position_components[flag];
+                                let color = if c_ecs.team_components.get(player_key) == Some(&flag_component.team) {
+                                    friend_tracker_color
+                                } else {
+                                    foe_tracker_color
+                                };
+                                tracker.draw_tracker(
+                                    &camera,
+                                    vec3(pos.x, pos.y, pos.z),
+                                    color,
+                                    &mut trackers,
+                                );
+                            }
                             // render particle effects                
                             shader_program.set_bool(c_str!("use_color"), true);
                             for i in (0..particle_emitters.len()).rev(){
@@ -860,19 +1100,16 @@ fn main() -> io::Result<()> {
                             shader_program.set_bool(c_str!("use_color"), false); 
                             
 
-                            show_game_over_screen = c_ecs.active_players <= 1;
+                            show_game_over_screen = c_ecs.round_over;
+
+                            force_field.radius = c_ecs.zone.radius;
+                            force_field.center = vec3(c_ecs.zone.x, c_ecs.zone.y, c_ecs.zone.z);
+                            hills = c_ecs.hills.clone();
+                            force_sources = c_ecs.force_sources.clone();
+                            nebulae = c_ecs.nebulae.clone();
 
                             // game has ended
                             if c_ecs.game_ended {
-                                for (i, player) in c_ecs.players.iter().enumerate() {
-                                    if  c_ecs.players.contains(player) &&
-                                        c_ecs.health_components[*player].alive &&
-                                        c_ecs.health_components[*player].health > 0
-                                    {
-                                        rankings.push(i);
-                                    }
-                                }
-                                rankings.reverse();
                                 game_state = GameState::GameOver;
                             }
                         }
@@ -900,6 +1137,18 @@ fn main() -> io::Result<()> {
                     gl::DepthMask(gl::FALSE);
 
                     force_field.draw(&camera, camera.Position.to_vec());
+                    for &shield_pos in &shielded_players {
+                        force_field.draw_shield(&camera, shield_pos);
+                    }
+                    for hill in &hills {
+                        force_field.draw_hill(&camera, vec3(hill.x, hill.y, hill.z), hill.radius, hill.active);
+                    }
+                    for source in &force_sources {
+                        force_field.draw_force_source(&camera, vec3(source.x, source.y, source.z), source.radius);
+                    }
+                    for nebula in &nebulae {
+                        force_field.draw_nebula(&camera, vec3(nebula.x, nebula.y, nebula.z), nebula.radius);
+                    }
                     tracers.draw_tracers(&camera);
 
                     // disable translucency for velocity indicator and first person model
@@ -910,7 +1159,7 @@ fn main() -> io::Result<()> {
 
                         arm.draw(&camera, &shader_program);
                         lights.init_lights(&shader_program, true);
-                        vel_indicator.draw(&camera, player_vel, width as f32 / height as f32, &shader_program);
+                        vel_indicator.draw(&camera, player_vel, player_dampening, width as f32 / height as f32, &shader_program);
                         lights.init_lights(&shader_program, false);
                     }
 
@@ -919,7 +1168,14 @@ fn main() -> io::Result<()> {
 
                     tracker.draw_all_trackers(trackers);
 
-                    ui_elems.draw_game(curr_id, client_health.alive, client_ammo, &client_ecs, spectator_mode, show_death_screen, show_game_over_screen);
+                    // another player's lasso is tied to us
+                    let roped = match &client_ecs {
+                        Some(c_ecs) => c_ecs.player_lasso_components.values().any(|lasso| lasso.tethered == Some(c_ecs.ids[client_id])),
+                        None => false,
+                    };
+                    // the camera is inside a nebula and can't see out
+                    let fogged = nebulae.iter().any(|nebula| (camera.Position.to_vec() - vec3(nebula.x, nebula.y, nebula.z)).magnitude() < nebula.radius);
+                    ui_elems.draw_game(curr_id, client_health.alive, client_ammo, &client_ecs, spectator_mode, show_death_screen, show_game_over_screen, roped, fogged);
 
                     // disable translucency for next loop
                     gl::DepthMask(gl::TRUE);
@@ -946,8 +1202,20 @@ fn main() -> io::Result<()> {
                     if process_inputs_game_over(&mut window, &mut first_enter) {
                         game_state = GameState::EnteringLobby;
                     }
+
+                    // poll server for the round results
+                    if round_results.is_none() {
+                        let received = read_data(&mut stream);
+                        if received.len() > 0 {
+                            let res: Result<RoundResults, bitcode::Error> = bitcode::deserialize(&received);
+                            if let Ok(results) = res {
+                                round_results = Some(results);
+                            }
+                        }
+                    }
+
                     gl::DepthMask(gl::FALSE);
-                    ui_elems.draw_game_over(curr_id, &client_ecs, &mut rankings);
+                    ui_elems.draw_game_over(curr_id, &client_ecs, &round_results);
                     gl::DepthMask(gl::TRUE);
                 }
             }
@@ -1,8 +1,98 @@
 use crate::fadable::Fadable;
 use crate::sprite_renderer::{Anchor, Sprite};
-use cgmath::{vec2, Vector2, vec3};
+use cgmath::{vec2, Vector2, vec3, Vector4};
 use shared::*;
 use shared::shared_components::*;
+use std::cmp::Reverse;
+use std::time::Instant;
+
+// colors of the strips under lobby player cards, indexed by team
+const TEAM_COLORS: [[f32; 4]; 4] = [
+    [224.0 / 255.0, 14.0 / 255.0, 115.0 / 255.0, 1.0],
+    [98.0 / 255.0, 168.0 / 255.0, 205.0 / 255.0, 1.0],
+    [252.0 / 255.0, 201.0 / 255.0, 0.0 / 255.0, 1.0],
+    [88.0 / 255.0, 180.0 / 255.0, 36.0 / 255.0, 1.0],
+];
+
+// match win pips on the leaderboard
+const PIP_WON_COLOR: [f32; 4] = [252.0 / 255.0, 201.0 / 255.0, 0.0 / 255.0, 1.0];
+const PIP_EMPTY_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.25];
+// screen border shown while another player's lasso is tied to us
+const ROPED_COLOR: [f32; 4] = [252.0 / 255.0, 201.0 / 255.0, 0.0 / 255.0, 0.8];
+const ROPED_BORDER: f32 = 6.0;
+// accuracy and survival time meters on the leaderboard
+const ACCURACY_COLOR: [f32; 4] = [224.0 / 255.0, 14.0 / 255.0, 115.0 / 255.0, 1.0];
+const SURVIVAL_COLOR: [f32; 4] = [98.0 / 255.0, 168.0 / 255.0, 205.0 / 255.0, 1.0];
+// thruster fuel gauge above the health bar, turns red when nearly empty
+const FUEL_COLOR: [f32; 4] = [252.0 / 255.0, 151.0 / 255.0, 0.0 / 255.0, 1.0];
+const FUEL_LOW_COLOR: [f32; 4] = [224.0 / 255.0, 40.0 / 255.0, 20.0 / 255.0, 1.0];
+const FUEL_LOW: f32 = 0.25;
+// dash cooldown gauge above the fuel gauge
+const DASH_READY_COLOR: [f32; 4] = [98.0 / 255.0, 168.0 / 255.0, 205.0 / 255.0, 1.0];
+const DASH_CHARGING_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.6];
+// spawn protection gauge, only shown while it lasts
+const SHIELD_COLOR: [f32; 4] = [88.0 / 255.0, 180.0 / 255.0, 36.0 / 255.0, 1.0];
+// capture progress of the active hill, coloured by who is taking it
+const HILL_OURS_COLOR: [f32; 4] = [98.0 / 255.0, 168.0 / 255.0, 205.0 / 255.0, 1.0];
+const HILL_THEIRS_COLOR: [f32; 4] = [224.0 / 255.0, 40.0 / 255.0, 20.0 / 255.0, 1.0];
+const HILL_CONTESTED_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.6];
+// kill feed in the top right corner, icon size is a fraction of the screen height
+const KILL_FEED_ICON: f32 = 0.035;
+const KILL_FEED_BORDER: f32 = 20.0;
+const KILL_FEED_SECONDS: f32 = 6.0;
+const KILL_FEED_FADE: f32 = 1.0;
+const KILL_FEED_LENGTH: usize = 5;
+// lifetime stats from the server's match history, shown in the lobby
+const RATING_COLOR: [f32; 4] = [252.0 / 255.0, 201.0 / 255.0, 0.0 / 255.0, 1.0];
+const WIN_RATE_COLOR: [f32; 4] = [88.0 / 255.0, 180.0 / 255.0, 36.0 / 255.0, 1.0];
+const LEADERBOARD_BG_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.7];
+const LEADERBOARD_OTHER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.4];
+// world event banner across the top of the screen, sizes are fractions of the screen
+const BANNER_WIDTH: f32 = 0.4;
+const BANNER_HEIGHT: f32 = 0.04;
+const BANNER_BORDER: f32 = 20.0;
+const BANNER_SECONDS: f32 = 4.0;
+const BANNER_FADE: f32 = 1.0;
+// screen tint while the camera is inside a nebula
+const FOG_COLOR: [f32; 4] = [88.0 / 255.0, 60.0 / 255.0, 120.0 / 255.0, 0.9];
+
+/**
+ * One line of the kill feed, players are indices into the player list
+ */
+struct KillFeedEntry {
+    // None when the player died to the environment on their own
+    killer: Option<usize>,
+    victim: usize,
+    cause: DamageSource,
+    assists: Vec<usize>,
+    shown: Instant,
+}
+
+/**
+ * Colour of the banner announcing a world event
+ */
+fn announcement_color(kind: WorldEventKind) -> [f32; 3] {
+    match kind {
+        WorldEventKind::MeteorShower => [252.0 / 255.0, 151.0 / 255.0, 0.0 / 255.0],
+        WorldEventKind::SupplyDrop => [88.0 / 255.0, 180.0 / 255.0, 36.0 / 255.0],
+        WorldEventKind::Nebula => [150.0 / 255.0, 100.0 / 255.0, 200.0 / 255.0],
+    }
+}
+
+/**
+ * Colour of the kill feed marker between killer and victim
+ */
+fn cause_color(cause: DamageSource) -> Vector4<f32> {
+    let c = match cause {
+        DamageSource::Shot => [1.0, 1.0, 1.0],
+        DamageSource::Headshot => [224.0 / 255.0, 40.0 / 255.0, 20.0 / 255.0],
+        DamageSource::Prop => [252.0 / 255.0, 201.0 / 255.0, 0.0 / 255.0],
+        DamageSource::Impact => [252.0 / 255.0, 151.0 / 255.0, 0.0 / 255.0],
+        DamageSource::Zone => [88.0 / 255.0, 180.0 / 255.0, 36.0 / 255.0],
+        DamageSource::OutOfBounds => [98.0 / 255.0, 168.0 / 255.0, 205.0 / 255.0],
+    };
+    Vector4::new(c[0], c[1], c[2], 1.0)
+}
 
 pub struct UI {
     // ========================== splash ui elements ==========================
@@ -15,6 +105,9 @@ pub struct UI {
     player_card_ready: [Sprite; 4],
     player_card_joined_me: [Sprite; 4],
     player_card_ready_me: [Sprite; 4],
+    team_marker: Sprite,
+    card_pos: [Vector2<f32>; 4],
+    card_size: Vector2<f32>,
 
     // =========================== game ui elements ===========================
     crosshair: Sprite,
@@ -30,13 +123,27 @@ pub struct UI {
     player_circle_dead: [Sprite; 4],
 
     death_messages: [[Option<Fadable>; 4]; 4],
+    kill_feed: Vec<KillFeedEntry>,
+    feed_players: [Sprite; 4],
+    feed_players_dead: [Sprite; 4],
+    feed_cause: Sprite,
 
     you_died_txt: Sprite,
     you_win_txt: Sprite,
     game_over_txt: Sprite,
 
+    roped_border: Sprite,
+    // latest world event and when it was announced
+    announcement: Option<(WorldEventKind, Instant)>,
+    banner: Sprite,
+    fog: Sprite,
+    screen_size: Vector2<f32>,
+    status_gauge: Sprite,
+    health_size: Vector2<f32>,
+
     pub damage: Fadable,
     pub hitmarker: Fadable,
+    pub headshot_hitmarker: Fadable,
     pub killmarkers: [Fadable; 4],
 
     // ======================== game over ui elements =========================
@@ -49,11 +156,15 @@ pub struct UI {
     bar_header: Sprite,
     leaderboard_bar: [Sprite; 4],
     
-    hits: [Sprite; 7],
+    hits: [Sprite; 10],
     player_txt: [Sprite; 4],
     player_you_txt: [Sprite; 4],
 
-    bar_pos: [Vector2<f32>; 4]
+    win_pip: Sprite,
+    meter: Sprite,
+
+    bar_pos: [Vector2<f32>; 4],
+    bar_size: Vector2<f32>,
 }
 
 impl UI {
@@ -86,6 +197,13 @@ impl UI {
         let death_message_pos = vec2(width / 2.0, height / 1.25);
         let screen_txt_pos = vec2(width / 2.0, height / 2.0);
 
+        // player cards are 1143x1600 textures scaled to PLAYER_SCALE of the screen width
+        let card_size = vec2(width * PLAYER_SCALE, width * PLAYER_SCALE * 1600.0 / 1143.0);
+        // leaderboard bars are 6750x600 textures
+        let bar_size = vec2(width * LEADERBOARD_SCALE, width * LEADERBOARD_SCALE * 600.0 / 6750.0);
+        // health bars are 1585x500 textures
+        let health_size = vec2(width * BAR_SCALE, width * BAR_SCALE * 500.0 / 1585.0);
+
         let death_message_fade = 0.3;
         let death_message_alpha = 3.0;
 
@@ -141,6 +259,10 @@ impl UI {
                 init_sprite(s_size, id, P4_READY_ME_PATH, p4_pos, PLAYER_SCALE),
             ],
 
+            team_marker: unsafe { Sprite::new(s_size, id) },
+            card_pos: [p1_pos, p2_pos, p3_pos, p4_pos],
+            card_size,
+
             // ================================ HUD ===================================
             crosshair: init_sprite(s_size, id, CROSSHAIR_PATH, bg_pos, CROSSHAIR_SCALE),
 
@@ -216,12 +338,36 @@ impl UI {
                 ]
             ],
 
+            kill_feed: vec![],
+            feed_players: [
+                init_sprite(s_size, id, P1_ALIVE_PATH, c1_pos, PLAYER_CIRCLE_SCALE),
+                init_sprite(s_size, id, P2_ALIVE_PATH, c2_pos, PLAYER_CIRCLE_SCALE),
+                init_sprite(s_size, id, P3_ALIVE_PATH, c3_pos, PLAYER_CIRCLE_SCALE),
+                init_sprite(s_size, id, P4_ALIVE_PATH, c4_pos, PLAYER_CIRCLE_SCALE),
+            ],
+            feed_players_dead: [
+                init_sprite(s_size, id, P1_DEAD_PATH, c1_pos, PLAYER_CIRCLE_SCALE),
+                init_sprite(s_size, id, P2_DEAD_PATH, c2_pos, PLAYER_CIRCLE_SCALE),
+                init_sprite(s_size, id, P3_DEAD_PATH, c3_pos, PLAYER_CIRCLE_SCALE),
+                init_sprite(s_size, id, P4_DEAD_PATH, c4_pos, PLAYER_CIRCLE_SCALE),
+            ],
+            feed_cause: init_sprite(s_size, id, HITMARKER_PATH, bg_pos, HITMARKER_SCALE),
+
             you_died_txt: init_sprite(s_size, id, YOU_DIED_TXT_PATH,screen_txt_pos, SCREEN_TXT_SCALE),
             you_win_txt: init_sprite(s_size, id, YOU_WIN_TXT_PATH, screen_txt_pos, SCREEN_TXT_SCALE),
             game_over_txt: init_sprite(s_size, id, GAME_OVER_TXT_PATH, screen_txt_pos, SCREEN_TXT_SCALE),
           
+            roped_border: unsafe { Sprite::new(s_size, id) },
+            announcement: None,
+            banner: unsafe { Sprite::new(s_size, id) },
+            fog: unsafe { Sprite::new(s_size, id) },
+            screen_size: vec2(width, height),
+            status_gauge: unsafe { Sprite::new(s_size, id) },
+            health_size,
+
             damage: Fadable::new(init_sprite(s_size, id, DAMAGE_PATH, bg_pos, LOBBY_BG_SCALE), 1.0, 1.0),
             hitmarker: Fadable::new(init_sprite(s_size, id, HITMARKER_PATH, bg_pos, HITMARKER_SCALE), 3.0, 2.0),
+            headshot_hitmarker: Fadable::new(init_sprite(s_size, id, HEADSHOT_HITMARKER_PATH, bg_pos, HITMARKER_SCALE * 1.3), 2.0, 2.0),
 
             killmarkers: [
                 Fadable::new(init_sprite(s_size, id, P1_KILLMARKER_PATH, bg_pos, HITMARKER_SCALE), 1.0, 2.0),
@@ -258,6 +404,9 @@ impl UI {
                 init_sprite(s_size, id, HITS_4_PATH, bar_1_pos, LEADERBOARD_SCALE),
                 init_sprite(s_size, id, HITS_5_PATH, bar_1_pos, LEADERBOARD_SCALE),
                 init_sprite(s_size, id, HITS_6_PATH, bar_1_pos, LEADERBOARD_SCALE),
+                init_sprite(s_size, id, HITS_7_PATH, bar_1_pos, LEADERBOARD_SCALE),
+                init_sprite(s_size, id, HITS_8_PATH, bar_1_pos, LEADERBOARD_SCALE),
+                init_sprite(s_size, id, HITS_9_PATH, bar_1_pos, LEADERBOARD_SCALE),
             ],
 
             player_txt: [
@@ -274,28 +423,42 @@ impl UI {
                 init_sprite(s_size, id, P4_YOU_TXT_PATH, bar_1_pos, LEADERBOARD_SCALE),
             ],
 
+            win_pip: unsafe { Sprite::new(s_size, id) },
+            meter: unsafe { Sprite::new(s_size, id) },
+
             bar_pos: [
                 bar_1_pos,
                 bar_2_pos,
                 bar_3_pos,
                 bar_4_pos,
-            ]
+            ],
+            bar_size,
         }
     }
 
-    pub fn draw_game(&mut self, client_id: usize, client_alive: bool, client_ammo: u8, c_ecs: &Option<ClientECS>, spectator_mode: bool, show_death_screen: bool, show_game_over_screen: bool) {
+    pub fn draw_game(&mut self, client_id: usize, client_alive: bool, client_ammo: u8, c_ecs: &Option<ClientECS>, spectator_mode: bool, show_death_screen: bool, show_game_over_screen: bool, roped: bool, fogged: bool) {
         unsafe {
+            if fogged {
+                self.fog.set_color(Vector4::new(FOG_COLOR[0], FOG_COLOR[1], FOG_COLOR[2], FOG_COLOR[3]));
+                self.fog.draw_from_corners(vec2(0.0, self.screen_size.y), vec2(self.screen_size.x, 0.0));
+            }
+
             if !spectator_mode {
                 self.crosshair.draw();
                 for killmarker in &mut self.killmarkers {
                     killmarker.draw();
                 }
                 self.hitmarker.draw();
+                self.headshot_hitmarker.draw();
 
                 self.ammo[client_ammo as usize].draw();
 
                 self.damage.draw();
 
+                if roped {
+                    self.draw_roped_border();
+                }
+
                 if show_death_screen && !show_game_over_screen {
                     self.you_died_txt.draw();
                 }
@@ -312,6 +475,28 @@ impl UI {
                         }
 
                         if i == client_id {
+                            if client_alive && !spectator_mode {
+                                if let Some(fuel) = ecs.fuel_components.get(*player) {
+                                    let c = if fuel.fuel < FUEL_LOW { FUEL_LOW_COLOR } else { FUEL_COLOR };
+                                    self.draw_status_gauge(0.0, fuel.fuel, c);
+                                }
+                                if let Some(dash) = ecs.dash_components.get(*player) {
+                                    let charge = 1.0 - dash.cooldown as f32 / ecs.dash_cooldown_ticks.max(1) as f32;
+                                    let c = if dash.cooldown == 0 { DASH_READY_COLOR } else { DASH_CHARGING_COLOR };
+                                    self.draw_status_gauge(1.0, charge, c);
+                                }
+                                let shield_ticks = ecs.health_components[*player].shield_ticks;
+                                if shield_ticks > 0 {
+                                    let shield = shield_ticks as f32 / ecs.spawn_protection_ticks.max(1) as f32;
+                                    self.draw_status_gauge(2.0, shield, SHIELD_COLOR);
+                                }
+                                if let Some(hill) = ecs.hills.iter().find(|hill| hill.active && hill.owner.is_some()) {
+                                    let ours = hill.owner == Some(*player) || hill.owner.and_then(|owner| ecs.team_components.get(owner))
+                                        .map_or(false, |team| ecs.team_components.get(*player) == Some(team));
+                                    let c = if hill.contested { HILL_CONTESTED_COLOR } else if ours { HILL_OURS_COLOR } else { HILL_THEIRS_COLOR };
+                                    self.draw_status_gauge(3.0, hill.progress, c);
+                                }
+                            }
                             if client_alive && ecs.health_components[*player].health == 2 {
                                 self.health_bar_full[client_id].draw();
                             } else if client_alive && ecs.health_components[*player].health == 1 {
@@ -344,6 +529,9 @@ impl UI {
                 }
             }
 
+            self.draw_kill_feed();
+            self.draw_announcement();
+
             if show_game_over_screen {
                 if client_alive {
                     self.you_win_txt.draw();
@@ -354,7 +542,7 @@ impl UI {
         }
     }
 
-    pub fn draw_lobby(&mut self, l: &mut LobbyECS, curr_id: usize) {
+    pub fn draw_lobby(&mut self, l: &mut LobbyECS, curr_id: usize, show_leaderboard: bool) {
         unsafe {
             self.lobby_bg[curr_id].draw();
 
@@ -370,14 +558,86 @@ impl UI {
                     if curr_id == i { self.player_card_joined_me[i].draw(); }
                     else { self.player_card_joined[i].draw(); }
                 }
+
+                // team strip under the card, press T to switch teams
+                if i < l.players.len() && l.num_teams > 0 {
+                    match l.team_components.get(l.players[i]) {
+                        Some(&team) => {
+                            let c = TEAM_COLORS[team as usize % TEAM_COLORS.len()];
+                            self.team_marker.set_color(Vector4::new(c[0], c[1], c[2], c[3]));
+                            let top_left = self.card_pos[i] + vec2(-self.card_size.x / 2.0, -self.card_size.y / 2.0 - 6.0);
+                            self.team_marker.draw_from_corners(top_left, top_left + vec2(self.card_size.x, -8.0));
+                        },
+                        None => ()
+                    }
+                }
+
+                // returning players get their rating and win rate under the card
+                if i < l.players.len() {
+                    if let Some(record) = l.records.get(l.players[i]) {
+                        let top_left = self.card_pos[i] + vec2(-self.card_size.x / 2.0, -self.card_size.y / 2.0 - 20.0);
+                        self.draw_bar(top_left, self.card_size.x, 6.0, rating_fill(l, record.rating), RATING_COLOR);
+                        self.draw_bar(top_left - vec2(0.0, 10.0), self.card_size.x, 6.0, record.win_rate, WIN_RATE_COLOR);
+                    }
+                }
+            }
+
+            if show_leaderboard {
+                self.draw_leaderboard(l);
+            }
+        }
+    }
+
+    /**
+     * Panel with a row per leaderboard entry: a marker for players in the lobby, their rating, then their win rate.
+     * There is no text rendering, so ratings are bars scaled between the lowest and highest shown
+     */
+    unsafe fn draw_leaderboard(&mut self, l: &LobbyECS) {
+        let row = self.screen_size.y * 0.045;
+        let width = self.screen_size.x * 0.4;
+        let height = row * l.leaderboard.len().max(1) as f32;
+        let top_left = vec2((self.screen_size.x - width) / 2.0, (self.screen_size.y + height) / 2.0);
+        let c = LEADERBOARD_BG_COLOR;
+        self.meter.set_color(Vector4::new(c[0], c[1], c[2], c[3]));
+        self.meter.draw_from_corners(top_left + vec2(-row / 2.0, row / 2.0), top_left + vec2(width + row / 2.0, -height - row / 2.0));
+
+        for (i, entry) in l.leaderboard.iter().enumerate() {
+            let top = top_left.y - row * i as f32;
+            let icon = row * 0.7;
+            // entries are kept under account names, which only the records of lobby players carry
+            let player = l.players.iter().position(|&player| l.records.get(player).map_or(false, |record| record.name == entry.name));
+            match player {
+                Some(player) => draw_icon(&mut self.feed_players[player % 4], top_left.x + icon, top - (row - icon) / 2.0, icon, Vector4::new(1.0, 1.0, 1.0, 1.0)),
+                None => {
+                    let c = LEADERBOARD_OTHER_COLOR;
+                    let size = icon * 0.4;
+                    self.meter.set_color(Vector4::new(c[0], c[1], c[2], c[3]));
+                    let corner = vec2(top_left.x + (icon - size) / 2.0, top - (row - size) / 2.0);
+                    self.meter.draw_from_corners(corner, corner + vec2(size, -size));
+                },
             }
+            let bar_left = top_left.x + icon * 1.5;
+            let bar_width = width - icon * 1.5;
+            self.draw_bar(vec2(bar_left, top - row * 0.2), bar_width, row * 0.3, rating_fill(l, entry.rating), RATING_COLOR);
+            self.draw_bar(vec2(bar_left, top - row * 0.6), bar_width, row * 0.15, entry.win_rate, WIN_RATE_COLOR);
         }
     }
 
-    pub fn draw_game_over(&mut self, curr_id: usize, c_ecs: &Option<ClientECS>, rankings: &mut Vec<usize>) {
+    /**
+     * Draw a horizontal bar with an empty track behind it
+     *
+     * @param   fill: how full the bar is, from 0 to 1
+     */
+    unsafe fn draw_bar(&mut self, top_left: Vector2<f32>, length: f32, thickness: f32, fill: f32, color: [f32; 4]) {
+        self.meter.set_color(Vector4::new(PIP_EMPTY_COLOR[0], PIP_EMPTY_COLOR[1], PIP_EMPTY_COLOR[2], PIP_EMPTY_COLOR[3]));
+        self.meter.draw_from_corners(top_left, top_left + vec2(length, -thickness));
+        self.meter.set_color(Vector4::new(color[0], color[1], color[2], color[3]));
+        self.meter.draw_from_corners(top_left, top_left + vec2(length * fill.clamp(0.0, 1.0), -thickness));
+    }
+
+    pub fn draw_game_over(&mut self, curr_id: usize, c_ecs: &Option<ClientECS>, round_results: &Option<RoundResults>) {
         unsafe{
             self.game_over_bg.draw();
-            self.winner_txt.draw();
             self.continue_txt.draw();
             self.bar_header.draw();
 
@@ -385,41 +645,228 @@ impl UI {
                 self.leaderboard_bar[i].draw();
             }
 
-            match c_ecs {
-                Some(ecs) => {
-                    for (i, player) in rankings.iter().enumerate() {
-                        if i == 0 {
-                            self.winner_card[*player].draw();
+            match (c_ecs, round_results) {
+                (Some(ecs), Some(round_results)) => {
+                    // between rounds of a match only the series standings are shown
+                    let series = ecs.rounds_per_match > 1;
+                    if ecs.match_over {
+                        self.winner_txt.draw();
+                    }
+
+                    let mut results: Vec<&RoundResult> = round_results.results.iter().filter(|result| ecs.players.contains(&result.player)).collect();
+                    results.sort_by_key(|result| result.placement);
+                    // over several rounds, rank by match wins then total kills
+                    if series {
+                        results.sort_by_key(|result| {
+                            let score = ecs.match_scores.get(result.player).copied().unwrap_or(MatchScore::default());
+                            (Reverse(score.wins), Reverse(score.kills))
+                        });
+                    }
+                    let longest_survival = results.iter().map(|result| result.survival_time).fold(0.0, f32::max);
+
+                    for (i, result) in results.iter().take(4).enumerate() {
+                        let player = ecs.players.iter().position(|&p| p == result.player).unwrap();
+                        if i == 0 && ecs.match_over {
+                            self.winner_card[player].draw();
                         }
 
-                        if curr_id == *player {
-                            self.player_you_txt[*player].set_position(self.bar_pos[i]);
-                            self.player_you_txt[*player].draw(); 
+                        if curr_id == player {
+                            self.player_you_txt[player].set_position(self.bar_pos[i]);
+                            self.player_you_txt[player].draw(); 
                         } else {
-                            self.player_txt[*player].set_position(self.bar_pos[i]);
-                            self.player_txt[*player].draw(); 
+                            self.player_txt[player].set_position(self.bar_pos[i]);
+                            self.player_txt[player].draw(); 
                         }
-                        
-                        let hit_count = &mut self.hits[ecs.health_components[ecs.players[*player]].hits as usize];
+
+                        let score = ecs.match_scores.get(result.player).copied().unwrap_or(MatchScore::default());
+                        let hits = if series { score.hits as usize } else { result.hits as usize };
+                        let hit_count = &mut self.hits[hits.min(self.hits.len() - 1)];
                         hit_count.set_position(self.bar_pos[i]);
                         hit_count.draw();
+
+                        if series {
+                            self.draw_win_pips(self.bar_pos[i], score.wins, ecs.rounds_per_match / 2 + 1);
+                        }
+                        let survival = if longest_survival > 0.0 { result.survival_time / longest_survival } else { 0.0 };
+                        self.draw_meter(self.bar_pos[i], -0.2, result.accuracy, ACCURACY_COLOR);
+                        self.draw_meter(self.bar_pos[i], -0.32, survival, SURVIVAL_COLOR);
                     }
                 }
-                None => ()
+                _ => ()
             }
         }
     }
 
+    /**
+     * Outline the screen to warn the player that they are lassoed, R cuts the rope
+     */
+    unsafe fn draw_roped_border(&mut self) {
+        let (w, h, b) = (self.screen_size.x, self.screen_size.y, ROPED_BORDER);
+        self.roped_border.set_color(Vector4::new(ROPED_COLOR[0], ROPED_COLOR[1], ROPED_COLOR[2], ROPED_COLOR[3]));
+        self.roped_border.draw_from_corners(vec2(0.0, h), vec2(w, h - b));
+        self.roped_border.draw_from_corners(vec2(0.0, b), vec2(w, 0.0));
+        self.roped_border.draw_from_corners(vec2(0.0, h), vec2(b, 0.0));
+        self.roped_border.draw_from_corners(vec2(w - b, h), vec2(w, 0.0));
+    }
+
+    /**
+     * Draw a thin status gauge stacked above the health bar
+     *
+     * @param   row: how many gauges sit between this one and the health bar
+     * @param   fill: how full the gauge is, from 0 to 1
+     */
+    unsafe fn draw_status_gauge(&mut self, row: f32, fill: f32, color: [f32; 4]) {
+        let length = self.health_size.x * 0.69;
+        let thickness = self.health_size.y * 0.12;
+        let top_left = vec2(BAR_BORDER + self.health_size.x * 0.30, BAR_BORDER + self.health_size.y + thickness * (1.0 + 2.0 * row));
+
+        self.status_gauge.set_color(Vector4::new(PIP_EMPTY_COLOR[0], PIP_EMPTY_COLOR[1], PIP_EMPTY_COLOR[2], PIP_EMPTY_COLOR[3]));
+        self.status_gauge.draw_from_corners(top_left, top_left + vec2(length, -thickness));
+        self.status_gauge.set_color(Vector4::new(color[0], color[1], color[2], color[3]));
+        self.status_gauge.draw_from_corners(top_left, top_left + vec2(length * fill.clamp(0.0, 1.0), -thickness));
+    }
+
+    /**
+     * Draw a thin horizontal meter in the empty middle of a leaderboard bar
+     *
+     * @param   offset: vertical offset from the bar's center, as a fraction of its height
+     * @param   fill: how full the meter is, from 0 to 1
+     */
+    unsafe fn draw_meter(&mut self, bar_pos: Vector2<f32>, offset: f32, fill: f32, color: [f32; 4]) {
+        let length = self.bar_size.x * 0.25;
+        let thickness = self.bar_size.y * 0.07;
+        let top_left = bar_pos + vec2(self.bar_size.x * 0.02, self.bar_size.y * offset);
+
+        self.meter.set_color(Vector4::new(PIP_EMPTY_COLOR[0], PIP_EMPTY_COLOR[1], PIP_EMPTY_COLOR[2], PIP_EMPTY_COLOR[3]));
+        self.meter.draw_from_corners(top_left, top_left + vec2(length, -thickness));
+        self.meter.set_color(Vector4::new(color[0], color[1], color[2], color[3]));
+        self.meter.draw_from_corners(top_left, top_left + vec2(length * fill.clamp(0.0, 1.0), -thickness));
+    }
+
+    /**
+     * Draw one pip per round needed to win the match, filled in for rounds already won
+     */
+    unsafe fn draw_win_pips(&mut self, bar_pos: Vector2<f32>, wins: u8, wins_needed: u8) {
+        let size = self.bar_size.y * 0.35;
+        for pip in 0..wins_needed.max(wins) {
+            let c = if pip < wins { PIP_WON_COLOR } else { PIP_EMPTY_COLOR };
+            self.win_pip.set_color(Vector4::new(c[0], c[1], c[2], c[3]));
+            let top_left = bar_pos + vec2(self.bar_size.x * 0.02 + size * 1.5 * pip as f32, self.bar_size.y * 0.12 + size / 2.0);
+            self.win_pip.draw_from_corners(top_left, top_left + vec2(size, -size));
+        }
+    }
+
     pub fn draw_splash(&mut self) {
         unsafe { self.splash.draw() };
     }
 
-    pub fn display_death_message(&mut self, killer: usize, player: usize) {
-        match &mut self.death_messages[killer][player] {
-            Some(message) => message.add_alpha(2.0),
-            None => eprintln!("Player {killer} shot themselves (Player {player})!")
+    /**
+     * Announce a death in the kill feed, kills by another player also get a banner
+     *
+     * @param   killer: index of the killer, the same as player for environmental deaths
+     * @param   cause: what dealt the final blow
+     * @param   assists: indices of other players who helped
+     */
+    pub fn display_death_message(&mut self, killer: usize, player: usize, cause: DamageSource, assists: Vec<usize>) {
+        if killer != player {
+            if let Some(message) = &mut self.death_messages[killer % 4][player % 4] {
+                message.add_alpha(2.0);
+            }
+        }
+        self.kill_feed.push(KillFeedEntry {
+            killer: if killer != player { Some(killer) } else { None },
+            victim: player,
+            cause,
+            assists,
+            shown: Instant::now(),
+        });
+        if self.kill_feed.len() > KILL_FEED_LENGTH {
+            self.kill_feed.remove(0);
+        }
+    }
+
+    /**
+     * Show a banner for a world event that just started, replacing any earlier one
+     */
+    pub fn announce(&mut self, kind: WorldEventKind) {
+        self.announcement = Some((kind, Instant::now()));
+    }
+
+    /**
+     * Draw the world event banner at the top of the screen, the strip under it runs out with its time
+     */
+    unsafe fn draw_announcement(&mut self) {
+        let (kind, shown) = match self.announcement {
+            Some(announcement) => announcement,
+            None => return,
+        };
+        let elapsed = shown.elapsed().as_secs_f32();
+        if elapsed >= BANNER_SECONDS {
+            self.announcement = None;
+            return;
         }
+        let alpha = ((BANNER_SECONDS - elapsed) / BANNER_FADE).min(1.0);
+        let (w, h) = (self.screen_size.x * BANNER_WIDTH, self.screen_size.y * BANNER_HEIGHT);
+        let top_left = vec2((self.screen_size.x - w) / 2.0, self.screen_size.y - BANNER_BORDER);
+        let c = announcement_color(kind);
+
+        self.banner.set_color(Vector4::new(c[0], c[1], c[2], alpha * 0.8));
+        self.banner.draw_from_corners(top_left, top_left + vec2(w, -h));
+        let strip = top_left - vec2(0.0, h * 1.2);
+        self.banner.set_color(Vector4::new(1.0, 1.0, 1.0, alpha * 0.6));
+        self.banner.draw_from_corners(strip, strip + vec2(w * (1.0 - elapsed / BANNER_SECONDS), -h * 0.15));
     }
+
+    /**
+     * Draw the kill feed newest first, each line reads killer, cause, victim, then smaller assist icons
+     */
+    unsafe fn draw_kill_feed(&mut self) {
+        self.kill_feed.retain(|entry| entry.shown.elapsed().as_secs_f32() < KILL_FEED_SECONDS);
+        let size = self.screen_size.y * KILL_FEED_ICON;
+        let small = size * 0.6;
+        let gap = size * 0.25;
+        for (row, entry) in self.kill_feed.iter().rev().enumerate() {
+            let alpha = ((KILL_FEED_SECONDS - entry.shown.elapsed().as_secs_f32()) / KILL_FEED_FADE).min(1.0);
+            let white = Vector4::new(1.0, 1.0, 1.0, alpha);
+            let top = self.screen_size.y - KILL_FEED_BORDER - row as f32 * (size + gap);
+            // lay the line out from the right edge
+            let mut right = self.screen_size.x - KILL_FEED_BORDER;
+            for &assist in entry.assists.iter().rev() {
+                draw_icon(&mut self.feed_players[assist % 4], right, top - (size - small) / 2.0, small, white);
+                right -= small + gap / 2.0;
+            }
+            if !entry.assists.is_empty() {
+                right -= gap / 2.0;
+            }
+            draw_icon(&mut self.feed_players_dead[entry.victim % 4], right, top, size, white);
+            right -= size + gap;
+            let mut color = cause_color(entry.cause);
+            color.w = alpha;
+            draw_icon(&mut self.feed_cause, right, top, size, color);
+            right -= size + gap;
+            if let Some(killer) = entry.killer {
+                draw_icon(&mut self.feed_players[killer % 4], right, top, size, white);
+            }
+        }
+    }
+}
+
+/**
+ * How far along a rating is between the lowest and highest rating the lobby knows about
+ */
+fn rating_fill(l: &LobbyECS, rating: f32) -> f32 {
+    let ratings = l.leaderboard.iter().map(|entry| entry.rating).chain(l.records.values().map(|entry| entry.rating));
+    let (low, high) = ratings.fold((rating, rating), |(low, high), r| (low.min(r), high.max(r)));
+    // with nothing to compare against, sit in the middle
+    if high - low < 1.0 { 0.5 } else { 0.1 + 0.9 * (rating - low) / (high - low) }
+}
+
+/**
+ * Draw a square icon given its right edge and top
+ */
+unsafe fn draw_icon(sprite: &mut Sprite, right: f32, top: f32, size: f32, color: Vector4<f32>) {
+    sprite.set_color(color);
+    sprite.draw_from_corners(vec2(right - size, top), vec2(right, top - size));
 }
 
 fn init_sprite(s_size: Vector2<f32>, shader_id: u32, path: &str, 
@@ -1,4 +1,4 @@
-use cgmath::{Matrix4, Point3, SquareMatrix, Transform, Vector3, InnerSpace, vec3, perspective, Deg, Array};
+use cgmath::{Matrix4, Point3, SquareMatrix, Transform, Vector3, Vector4, InnerSpace, vec3, perspective, Deg, Array};
 use crate::camera::Camera;
 use crate::model::Model;
 use crate::shader::Shader;
@@ -6,6 +6,8 @@ use shared::*;
 use std::{ffi::{CStr}, time::Instant};
 
 const LERP_RATE: f32 = 50.0;
+// arrow tint while the inertial dampeners are on
+const DAMPENING_COLOR: Vector4<f32> = Vector4 { x: 98.0 / 255.0, y: 168.0 / 255.0, z: 205.0 / 255.0, w: 1.0 };
 
 pub struct VelocityIndicator {
     model: Model,
@@ -24,7 +26,7 @@ impl VelocityIndicator {
         velocity_indicator
     }
 
-    pub unsafe fn draw(&mut self, camera: &Camera, velocity: Vector3<f32>, aspect_ratio: f32, shader: &Shader) {
+    pub unsafe fn draw(&mut self, camera: &Camera, velocity: Vector3<f32>, dampening: bool, aspect_ratio: f32, shader: &Shader) {
         let now = Instant::now();
         let delta = now.duration_since(self.prev).as_secs_f32();
         self.prev = now;
@@ -60,6 +62,11 @@ impl VelocityIndicator {
         let light_ambience = Vector3::from_value(0.5);
         shader.set_vector3(c_str!("lightAmb"), &light_ambience);
 
+        if dampening {
+            shader.set_bool(c_str!("use_color"), true);
+            shader.set_vector4(c_str!("color_overwrite"), &DAMPENING_COLOR);
+        }
         self.model.draw(shader);
+        shader.set_bool(c_str!("use_color"), false);
     }
 }
\ No newline at end of file
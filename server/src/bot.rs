@@ -0,0 +1,248 @@
+use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+use rand::{thread_rng, Rng};
+use rapier3d::prelude::*;
+use slotmap::{DefaultKey, KeyData};
+
+use shared::*;
+use shared::shared_components::*;
+use crate::ecs::ECS;
+use crate::server_components::*;
+
+type Entity = DefaultKey;
+
+// bots pick a new waypoint once they are this close to theirs
+const ARRIVE_DIST: f32 = 20.0;
+// waypoints further away than this are reached by lassoing them
+const LASSO_DIST: f32 = 40.0;
+// how far off target a bot's aim may be when it shoots, shrinks with skill
+const AIM_TOLERANCE: f32 = 0.12;
+// largest aim offset of a bot with no skill, in radians
+const AIM_ERROR: f32 = 0.1;
+
+/**
+ * Bots are ordinary players without a network component, each tick they fill in the
+ * same inputs a client would send
+ */
+impl ECS {
+    /**
+     * Add bots until the lobby reaches the configured size, or remove them to make room for
+     * people joining. Bots are always ready and leave once nobody else is left
+     */
+    pub fn fill_bots(&mut self) {
+        let humans = self.players.iter().filter(|&&player| !self.bot_components.contains_key(player)).count();
+        let size = if humans == 0 { 0 } else { (self.config.bots.fill_to as usize).min(MAX_PLAYERS).max(humans) };
+        while self.players.len() < size {
+            let bot = self.join_player("bot".to_string());
+            self.bot_components.insert(bot, BotComponent::default());
+        }
+        while self.players.len() > size {
+            match self.players.iter().rev().find(|&&player| self.bot_components.contains_key(player)) {
+                Some(&bot) => self.remove_player(bot),
+                None => break,
+            }
+        }
+        for &player in &self.players {
+            if self.bot_components.contains_key(player) {
+                self.ready_players.insert(player, true);
+            }
+        }
+    }
+
+    /**
+     * Fill in the inputs and camera of every living bot for this tick
+     */
+    pub fn update_bots(&mut self) {
+        let bots: Vec<Entity> = self.players.iter().copied().filter(|&player| self.bot_components.contains_key(player)).collect();
+        for bot in bots {
+            if !self.player_health_components[bot].alive {
+                continue;
+            }
+            let input = self.bot_input(bot);
+            self.player_camera_components[bot].look(UnitQuaternion::from_quaternion(Quaternion::new(
+                input.camera_qw,
+                input.camera_qx,
+                input.camera_qy,
+                input.camera_qz)));
+            self.player_input_components[bot] = input;
+        }
+    }
+
+    /**
+     * Decide what a bot does this tick: fight the nearest opponent in sight, otherwise travel
+     * between props by thrusting, lassoing them or firing away from them for recoil
+     */
+    fn bot_input(&mut self, bot: Entity) -> PlayerInputComponent {
+        let config = self.config.bots.clone();
+        let skill = config.skill.clamp(0.0, 1.0);
+        let mut brain = std::mem::replace(&mut self.bot_components[bot], BotComponent::default());
+        let mut input = PlayerInputComponent::default();
+
+        let body = &self.rigid_body_set[self.physics_components[bot].handle];
+        let pos = *body.translation();
+        let vel = *body.linvel();
+        let aim = self.player_camera_components[bot].rot;
+        let eye = pos + aim * Vector3::y() * 0.5;
+
+        // give an opponent the bot just spotted a moment before shooting at them
+        let target = self.bot_find_target(bot, &eye, config.view_dist);
+        if target != brain.target {
+            brain.target = target;
+            brain.reaction = config.reaction_ticks;
+        } else {
+            brain.reaction = brain.reaction.saturating_sub(1);
+        }
+
+        let waypoint_gone = brain.waypoint.map_or(true, |waypoint| !self.physics_components.contains_key(waypoint));
+        if waypoint_gone {
+            brain.waypoint = self.bot_pick_waypoint(&pos);
+        }
+        let waypoint_pos = match brain.waypoint {
+            Some(waypoint) => *self.rigid_body_set[self.physics_components[waypoint].handle].translation(),
+            None => Vector3::new(self.zone.x, self.zone.y, self.zone.z),
+        };
+        let to_waypoint = waypoint_pos - pos;
+        let waypoint_dist = to_waypoint.norm();
+        let anchor = self.player_lasso_phys_components.get(bot).map(|lasso| lasso.anchor);
+        let throwing = self.player_lasso_thrown_components.contains_key(bot);
+
+        let mut look = to_waypoint;
+        let mut fire = false;
+        match target {
+            Some(target) => {
+                let target_pos = *self.rigid_body_set[self.physics_components[target].handle].translation();
+                look = (target_pos - eye).normalize() + brain.aim_error;
+                fire = brain.reaction == 0;
+            },
+            None if waypoint_dist > LASSO_DIST => {
+                // a lasso left on anything but the waypoint is let go by not holding the button
+                if throwing || (anchor.is_some() && anchor == brain.waypoint) {
+                    // hold on and reel in towards the waypoint
+                    input.rmb_clicked = true;
+                    input.q_pressed = anchor.is_some();
+                } else if anchor.is_none() && self.bot_can_see(bot, &eye, brain.waypoint) {
+                    input.rmb_clicked = aim_angle(&aim, &to_waypoint) < AIM_TOLERANCE;
+                } else if anchor.is_none() && vel.norm() < config.cruise_speed / 2.0 && self.player_weapon_components[bot].ammo == AMMO_COUNT {
+                    // nothing to lasso, shoot the other way and ride the recoil
+                    look = -to_waypoint;
+                    fire = true;
+                }
+            },
+            None => (),
+        }
+        if waypoint_dist < ARRIVE_DIST {
+            brain.waypoint = None;
+        }
+
+        // turn towards where the bot wants to look, faster for more skilled bots
+        let max_turn = 0.03 + 0.12 * skill;
+        let desired = face(&look, &aim);
+        let angle = aim.angle_to(&desired);
+        let aim = if angle <= max_turn { desired } else { aim.try_slerp(&desired, max_turn / angle, 1.0e-6).unwrap_or(desired) };
+
+        let tolerance = AIM_TOLERANCE * (1.0 - skill) + 0.02;
+        if fire && aim_angle(&aim, &look) < tolerance {
+            input.lmb_clicked = true;
+            let mut rng = thread_rng();
+            let error = Vector3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+            brain.aim_error = error * AIM_ERROR * (1.0 - skill);
+        }
+
+        // thrust to match the velocity that carries the bot to its waypoint
+        let speed = if target.is_some() { config.cruise_speed / 2.0 } else { config.cruise_speed };
+        let desired_vel = if waypoint_dist > 0.0 { to_waypoint / waypoint_dist * speed * (waypoint_dist / ARRIVE_DIST).min(1.0) } else { Vector3::zeros() };
+        let dv = desired_vel - vel;
+        let (front, right, up) = (aim * -Vector3::z(), aim * Vector3::x(), aim * Vector3::y());
+        input.w_pressed = dv.dot(&front) > 0.5;
+        input.s_pressed = dv.dot(&front) < -0.5;
+        input.d_pressed = dv.dot(&right) > 0.5;
+        input.a_pressed = dv.dot(&right) < -0.5;
+        input.shift_pressed = dv.dot(&up) > 0.5;
+        input.ctrl_pressed = dv.dot(&up) < -0.5;
+        input.x_pressed = vel.norm() > config.cruise_speed * 1.5;
+        input.v_pressed = target.is_some() && thread_rng().gen::<f32>() < 0.005 * skill;
+        // cut any rope tied to the bot
+        input.r_pressed = self.player_lasso_components.values().any(|lasso| lasso.tethered == Some(bot));
+
+        input.camera_qx = aim.i;
+        input.camera_qy = aim.j;
+        input.camera_qz = aim.k;
+        input.camera_qw = aim.w;
+        self.bot_components[bot] = brain;
+        input
+    }
+
+    /**
+     * Nearest living opponent the bot has a clear shot at
+     */
+    fn bot_find_target(&self, bot: Entity, eye: &Vector3<f32>, view_dist: f32) -> Option<Entity> {
+        self.players.iter().copied()
+            .filter(|&other| other != bot && self.player_health_components[other].alive && !ECS::same_team(&self.team_components, bot, other))
+            // opponents inside a nebula can't be seen at all
+            .filter(|&other| !self.in_nebula(self.rigid_body_set[self.physics_components[other].handle].translation()))
+            .map(|other| (other, (self.rigid_body_set[self.physics_components[other].handle].translation() - eye).norm()))
+            .filter(|&(other, dist)| dist < view_dist && self.bot_can_see(bot, eye, Some(other)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(other, _)| other)
+    }
+
+    /**
+     * Check if nothing stands between a bot and an entity
+     */
+    fn bot_can_see(&self, bot: Entity, eye: &Vector3<f32>, entity: Option<Entity>) -> bool {
+        let entity = match entity {
+            Some(entity) => entity,
+            None => return false,
+        };
+        let to_entity = self.rigid_body_set[self.physics_components[entity].handle].translation() - eye;
+        let dist = to_entity.norm();
+        if dist == 0.0 {
+            return true;
+        }
+        let ray = Ray::new(Point::from(*eye), to_entity / dist);
+        let filter = QueryFilter::new().exclude_rigid_body(self.physics_components[bot].handle);
+        match self.query_pipeline.cast_ray(&self.rigid_body_set, &self.collider_set, &ray, dist, true, filter) {
+            Some((handle, _)) => DefaultKey::from(KeyData::from_ffi(self.collider_set[handle].user_data as u64)) == entity,
+            None => true,
+        }
+    }
+
+    /**
+     * Random prop well inside the play zone that the bot isn't already next to
+     */
+    fn bot_pick_waypoint(&self, pos: &Vector3<f32>) -> Option<Entity> {
+        let center = Vector3::new(self.zone.x, self.zone.y, self.zone.z);
+        let props: Vec<Entity> = self.renderables.iter().copied()
+            .filter(|&prop| !self.players.contains(&prop) && self.physics_components.contains_key(prop))
+            .filter(|&prop| {
+                let prop_pos = self.rigid_body_set[self.physics_components[prop].handle].translation();
+                (prop_pos - center).norm() < self.zone.radius * 0.8 && (prop_pos - pos).norm() > ARRIVE_DIST
+            })
+            .collect();
+        if props.is_empty() {
+            return None;
+        }
+        Some(props[thread_rng().gen_range(0..props.len())])
+    }
+}
+
+/**
+ * Rotation that looks along dir, keeping the current up direction where possible
+ */
+fn face(dir: &Vector3<f32>, aim: &UnitQuaternion<f32>) -> UnitQuaternion<f32> {
+    if dir.norm() == 0.0 {
+        return *aim;
+    }
+    let mut up = aim * Vector3::y();
+    if dir.normalize().cross(&up).norm() < 1.0e-3 {
+        up = aim * Vector3::z();
+    }
+    // cameras look down their -z axis
+    UnitQuaternion::face_towards(&-dir, &up)
+}
+
+/**
+ * Angle between where a rotation looks and a direction
+ */
+fn aim_angle(aim: &UnitQuaternion<f32>, dir: &Vector3<f32>) -> f32 {
+    (aim * -Vector3::z()).angle(dir)
+}
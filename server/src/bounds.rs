@@ -0,0 +1,101 @@
+use nalgebra::Vector3;
+use slotmap::DefaultKey;
+
+use shared::shared_components::*;
+use crate::ecs::ECS;
+use crate::server_config::{PlayerBoundsPolicy, PropBoundsPolicy};
+
+type Entity = DefaultKey;
+
+/**
+ * Anything launched out through the border would otherwise drift away forever, still simulated
+ * and sent to every client. Whatever passes the map bound is dealt with by the configured policy
+ */
+impl ECS {
+    /**
+     * Find props and players beyond the map bound and recover them
+     */
+    pub fn check_bounds(&mut self) {
+        let bounds = self.config.bounds.clone();
+        if !bounds.enabled {
+            return;
+        }
+        let center = Vector3::new(bounds.center[0], bounds.center[1], bounds.center[2]);
+        let escaped: Vec<Entity> = self.dynamics.iter().copied()
+            .filter(|&entity| !self.kinematic_components.contains_key(entity))
+            // thrown lassos are pulled back by player_lasso once they fly past their range
+            .filter(|&entity| !self.player_lasso_thrown_components.values().any(|thrown| thrown.entity == entity))
+            .filter(|&entity| (self.rigid_body_set[self.physics_components[entity].handle].translation() - center).norm() > bounds.radius)
+            .collect();
+
+        for entity in escaped {
+            if self.players.contains(&entity) {
+                match bounds.players {
+                    PlayerBoundsPolicy::Kill => self.kill_escaped_player(entity),
+                    PlayerBoundsPolicy::Teleport => self.teleport_player(entity),
+                    PlayerBoundsPolicy::Ignore => (),
+                }
+                continue;
+            }
+            // flags always go back to their base, whatever the policy
+            if self.flag_components.contains_key(entity) {
+                self.return_flag(entity, None);
+                continue;
+            }
+            match bounds.props {
+                // props spawned mid-round have nowhere to go back to
+                PropBoundsPolicy::Respawn if self.prop_origins.contains_key(entity) => self.reset_prop(entity),
+                PropBoundsPolicy::Respawn | PropBoundsPolicy::Despawn => self.remove_prop(entity),
+                PropBoundsPolicy::Ignore => (),
+            }
+        }
+    }
+
+    /**
+     * Put a prop back where the world file placed it, at rest
+     */
+    fn reset_prop(&mut self, prop: Entity) {
+        self.release_lassos_on(prop);
+        self.thrown_prop_components.remove(prop);
+        let body = &mut self.rigid_body_set[self.physics_components[prop].handle];
+        body.set_position(self.prop_origins[prop], true);
+        body.set_linvel(Vector3::zeros(), true);
+        body.set_angvel(Vector3::zeros(), true);
+    }
+
+    /**
+     * Kill a living player who escaped, crediting whoever sent them flying. Dead bodies are
+     * stopped where they are until the player respawns
+     */
+    fn kill_escaped_player(&mut self, player: Entity) {
+        if !self.player_health_components[player].alive {
+            let body = &mut self.rigid_body_set[self.physics_components[player].handle];
+            body.set_linvel(Vector3::zeros(), true);
+            body.set_angvel(Vector3::zeros(), true);
+            return;
+        }
+        let attacker = match self.last_attacker_components.get(player) {
+            Some(last_attacker) if self.players.contains(&last_attacker.attacker) => last_attacker.attacker,
+            _ => player,
+        };
+        self.release_lasso(player);
+        self.release_lassos_on(player);
+        // spawn protection doesn't save anyone from leaving the map
+        self.player_health_components[player].shield_ticks = 0;
+        self.damage_player(player, attacker, u8::MAX, DamageSource::OutOfBounds);
+    }
+
+    /**
+     * Move an escaped player, living or dead, to a spawn point at rest
+     */
+    fn teleport_player(&mut self, player: Entity) {
+        self.release_lasso(player);
+        self.release_lassos_on(player);
+        let threats = self.spawn_threats(player);
+        let pos = self.take_spawnpoint(player, &threats);
+        let body = &mut self.rigid_body_set[self.physics_components[player].handle];
+        body.set_position(pos, true);
+        body.set_linvel(Vector3::zeros(), true);
+        body.set_angvel(Vector3::zeros(), true);
+    }
+}
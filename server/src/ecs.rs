@@ -4,15 +4,17 @@ use slotmap::{SlotMap, SecondaryMap, DefaultKey, Key, KeyData, SparseSecondaryMa
 use std::collections::HashMap;
 use std::io::{Read, Write, self};
 use std::net::TcpListener;
+use rapier3d::crossbeam::channel::Receiver;
 
 use shared::*;
 use shared::shared_components::*;
-use crate::{server_components::*, init_world::*, common::*};
+use crate::{server_components::*, server_config::*, init_world::*, common::*};
+use crate::history::History;
 
 
 type Entity = DefaultKey;
 
-const EVENT_LIFETIME: u8 = 5;
+pub const EVENT_LIFETIME: u8 = 5;
 pub struct ECS {
     pub name_components: SlotMap<Entity, String>,
     
@@ -20,21 +22,46 @@ pub struct ECS {
     pub player_input_components: SecondaryMap<Entity, PlayerInputComponent>,
     pub position_components: SecondaryMap<Entity, PositionComponent>,
     pub player_weapon_components: SecondaryMap<Entity, PlayerWeaponComponent>,
+    pub player_fuel_components: SecondaryMap<Entity, PlayerFuelComponent>,
+    pub player_dash_components: SecondaryMap<Entity, PlayerDashComponent>,
     pub player_lasso_components: SecondaryMap<Entity, PlayerLassoComponent>,
     pub model_components: SecondaryMap<Entity, ModelComponent>,
     pub player_health_components: SecondaryMap<Entity, PlayerHealthComponent>,
     pub velocity_components: SecondaryMap<Entity, VelocityComponent>,
     pub particle_components: SecondaryMap<Entity, ParticleComponent>,
+    pub flag_components: SecondaryMap<Entity, FlagComponent>,
 
     // server components
     pub physics_components: SecondaryMap<Entity, PhysicsComponent>,
     pub network_components: SecondaryMap<Entity, NetworkComponent>,
+    pub bot_components: SecondaryMap<Entity, BotComponent>,
     pub player_camera_components: SecondaryMap<Entity, PlayerCameraComponent>,
     pub player_lasso_phys_components: SecondaryMap<Entity, PlayerLassoPhysComponent>,
     pub player_lasso_thrown_components: SecondaryMap<Entity, PlayerLassoThrownComponent>,
+    // ticks left until a lassoed player cuts the rope, keyed by the rope's owner
+    pub lasso_cut_timers: SecondaryMap<Entity, u16>,
+    pub thrown_prop_components: SecondaryMap<Entity, ThrownPropComponent>,
+    pub last_attacker_components: SecondaryMap<Entity, LastAttackerComponent>,
+    pub damage_history: SecondaryMap<Entity, Vec<DamageRecord>>,
+    pub impact_cooldowns: SecondaryMap<Entity, u16>,
     pub event_components: SecondaryMap<Entity, EventComponent>,
+    pub team_components: SecondaryMap<Entity, u8>,
+    // name the client asked to keep its match history under, display names are handed out by colour
+    pub account_names: SecondaryMap<Entity, String>,
+    // ticks until a dropped flag returns to its base
+    pub flag_return_timers: SecondaryMap<Entity, u16>,
+    // shots left before a destructible prop breaks
+    pub prop_health: SecondaryMap<Entity, u16>,
+    // ticks until a fragment of a broken prop disappears
+    pub fragment_timers: SecondaryMap<Entity, u16>,
+    pub kinematic_components: SecondaryMap<Entity, KinematicComponent>,
+    // supply crates from world events, and how close a player has to get to open one
+    pub supply_crates: SecondaryMap<Entity, f32>,
+    // where the world file placed each dynamic prop, for putting back props that leave the map
+    pub prop_origins: SecondaryMap<Entity, Isometry3<f32>>,
 
     pub moving: SparseSecondaryMap<Entity, bool>,
+    pub dampening: SecondaryMap<Entity, bool>,
 
     // physics objects
     pub rigid_body_set: RigidBodySet,
@@ -56,27 +83,51 @@ pub struct ECS {
     pub renderables: Vec<Entity>,
 
     pub decomps: HashMap<(String, i32),SharedShape>,
+    // model radius at scale 1, keyed by mesh path
+    pub model_radii: HashMap<String, f32>,
     pub events: Vec<Entity>,
 
-    pub spawnpoints: Vec<Isometry3<f32>>,
+    pub config: ServerConfig,
+    pub spawnpoints: Vec<PlayerSpawn>,
     pub skies: Vec<usize>,
     pub sky: usize,
+    pub standings: Vec<Standing>,
+    pub match_scores: SecondaryMap<Entity, MatchScore>,
+    pub round_number: u8,
     pub active_players: u8,
+    pub round_over: bool,
+    pub match_over: bool,
+    pub zone: PlayZone,
+    pub zone_ticks: u32,
+    pub zone_damage_timers: SecondaryMap<Entity, u16>,
+    pub hills: Vec<HillComponent>,
+    pub force_sources: Vec<ForceSource>,
+    pub world_events: Vec<ScheduledEvent>,
+    // ticks since the round started, for the world event schedule
+    pub world_event_ticks: u32,
+    pub nebulae: Vec<Nebula>,
+    pub hill_ticks: u32,
+    // player credited with holding the active hill this tick
+    pub hill_holder: Option<Entity>,
     pub game_ended: bool,
     pub eor_countdown: u16,
+    pub history: History,
 }
 
 impl ECS {
     /**
      * Initialize an ECS
      */
-    pub fn new() -> ECS {
+    pub fn new(config: ServerConfig) -> ECS {
+        let history = History::load(&config.history);
         ECS {
             name_components: SlotMap::new(),
 
             player_input_components: SecondaryMap::new(),
             position_components: SecondaryMap::new(),
             player_weapon_components: SecondaryMap::new(),
+            player_fuel_components: SecondaryMap::new(),
+            player_dash_components: SecondaryMap::new(),
             player_lasso_components: SecondaryMap::new(),
             model_components: SecondaryMap::new(),
             player_health_components: SecondaryMap::new(),
@@ -84,13 +135,29 @@ impl ECS {
 
             physics_components: SecondaryMap::new(),
             network_components: SecondaryMap::new(),
+            bot_components: SecondaryMap::new(),
             player_camera_components: SecondaryMap::new(),
             player_lasso_phys_components: SecondaryMap::new(),
             player_lasso_thrown_components: SecondaryMap::new(),
+            lasso_cut_timers: SecondaryMap::new(),
+            thrown_prop_components: SecondaryMap::new(),
+            last_attacker_components: SecondaryMap::new(),
+            damage_history: SecondaryMap::new(),
+            impact_cooldowns: SecondaryMap::new(),
             event_components: SecondaryMap::new(),
+            team_components: SecondaryMap::new(),
+            account_names: SecondaryMap::new(),
+            flag_return_timers: SecondaryMap::new(),
+            prop_health: SecondaryMap::new(),
+            fragment_timers: SecondaryMap::new(),
+            kinematic_components: SecondaryMap::new(),
+            supply_crates: SecondaryMap::new(),
+            prop_origins: SecondaryMap::new(),
 
             moving: SparseSecondaryMap::new(),
+            dampening: SecondaryMap::new(),
             particle_components: SecondaryMap::new(),
+            flag_components: SecondaryMap::new(),
 
             rigid_body_set: RigidBodySet::new(),
             collider_set: ColliderSet::new(),
@@ -111,14 +178,32 @@ impl ECS {
             renderables: vec![],
 
             decomps: HashMap::new(),
+            model_radii: HashMap::new(),
             events: vec![],
 
+            config,
             spawnpoints: vec![],
             skies: vec![],
             sky: 0,
+            standings: vec![],
+            match_scores: SecondaryMap::new(),
+            round_number: 0,
             active_players: 0,
+            round_over: false,
+            match_over: false,
+            zone: PlayZone::default(),
+            zone_ticks: 0,
+            zone_damage_timers: SecondaryMap::new(),
+            hills: vec![],
+            force_sources: vec![],
+            world_events: vec![],
+            world_event_ticks: 0,
+            nebulae: vec![],
+            hill_ticks: 0,
+            hill_holder: None,
             game_ended: false,
             eor_countdown: 250, // about 4 seconds
+            history,
         }
     }
 
@@ -129,7 +214,7 @@ impl ECS {
         // remove disconnected players
         let mut disconnected_players: Vec<Entity> = vec![];
         for &player in &self.players {
-            if !self.network_components[player].connected {
+            if !self.connected(player) {
                 disconnected_players.push(player);
             }
         }
@@ -154,35 +239,64 @@ impl ECS {
         self.player_input_components.retain(|key, _| self.players.contains(&key));
         self.position_components.retain(|key, _| self.players.contains(&key));
         self.player_weapon_components.retain(|key, _| self.players.contains(&key));
+        self.player_fuel_components.retain(|key, _| self.players.contains(&key));
+        self.player_dash_components.retain(|key, _| self.players.contains(&key));
         self.model_components.retain(|key, _| self.players.contains(&key));
         self.player_health_components.retain(|key, _| self.players.contains(&key));
         self.velocity_components.retain(|key, _| self.players.contains(&key));
         self.physics_components.retain(|key, _| self.players.contains(&key));
         self.network_components.retain(|key, _| self.players.contains(&key));
+        for bot in self.bot_components.values_mut() {
+            *bot = BotComponent::default();
+        }
         self.player_camera_components.retain(|key, _| self.players.contains(&key));
+        self.team_components.retain(|key, _| self.players.contains(&key));
+        self.account_names.retain(|key, _| self.players.contains(&key));
+        self.match_scores.retain(|key, _| self.players.contains(&key));
         self.player_lasso_components.clear();
         self.player_lasso_phys_components.clear();
         self.player_lasso_thrown_components.clear();
+        self.lasso_cut_timers.clear();
+        self.thrown_prop_components.clear();
+        self.last_attacker_components.clear();
+        self.damage_history.retain(|key, _| self.players.contains(&key));
+        for history in self.damage_history.values_mut() {
+            history.clear();
+        }
+        self.impact_cooldowns.clear();
         self.event_components.clear();
         self.particle_components.clear();
+        self.flag_components.clear();
+        self.flag_return_timers.clear();
+        self.prop_health.clear();
+        self.fragment_timers.clear();
+        self.kinematic_components.clear();
+        self.prop_origins.clear();
         self.dynamics.clear();
         self.renderables.clear();
         self.events.clear();
 
         init_world(self);
+        init_forces(self);
         init_player_spawns(&mut self.spawnpoints);
+        self.spawn_flags();
+        self.spawn_hills();
+        self.schedule_world_events();
         if self.skies.is_empty() {
             self.skies = (0..init_num_skies()).collect();
         }
         self.sky = get_rand_from_vec(&mut self.skies);
 
         for (index, &player) in self.players.iter().enumerate() {
-            if !self.network_components[player].connected {
+            if !self.connected(player) {
                 panic!("a disconnected player found");
             }
 
             self.player_input_components[player] = PlayerInputComponent::default();
             self.player_weapon_components[player] = PlayerWeaponComponent::default();
+            self.player_fuel_components[player] = PlayerFuelComponent::default();
+            self.player_dash_components[player] = PlayerDashComponent::default();
+            self.dampening[player] = false;
             self.player_camera_components[player] = PlayerCameraComponent::default();
             self.player_health_components[player] = PlayerHealthComponent::default();
 
@@ -196,13 +310,20 @@ impl ECS {
             let mass = collider.mass_properties().mass();
             let principal_inertia = collider.mass_properties().principal_inertia();
             collider.set_mass_properties(MassProperties::new(local_com, mass, principal_inertia * 10.0));
+            collider.set_active_events(ActiveEvents::CONTACT_FORCE_EVENTS);
+            collider.set_contact_force_event_threshold(self.impact_force_threshold(mass));
             let collider_handle = self.collider_set.insert_with_parent(collider, handle, &mut self.rigid_body_set);
+            self.collider_set.insert_with_parent(ECS::head_collider(player, index), handle, &mut self.rigid_body_set);
             self.physics_components[player] = PhysicsComponent{handle, collider_handle};
             self.dynamics.push(player);
             self.renderables.push(player);
         }
 
+        self.standings.clear();
         self.active_players = self.players.len() as u8;
+        self.round_over = false;
+        self.zone_ticks = 0;
+        self.zone_damage_timers.clear();
         self.game_ended = false;
         self.eor_countdown = 250; // about 4 seconds
     }
@@ -227,11 +348,8 @@ impl ECS {
                 println!("Client connected: {addr:?}, client id: {}", self.ids.len());
                 curr_stream.set_nonblocking(true).expect("Failed to set stream as nonblocking");
                 let name = "dummy".to_string();     // TODO: get name from client
-                let player = self.new_player(name.clone());
+                let player = self.join_player(name.clone());
                 self.network_components.insert(player, NetworkComponent{connected: true, stream: curr_stream});
-                self.player_health_components.insert(player, PlayerHealthComponent::default());
-                self.moving.insert(player, false);
-                self.active_players += 1;
                 self.send_ready_message(false);
             },
             Err(e) => {
@@ -248,6 +366,10 @@ impl ECS {
         let mut disconnected_players: Vec<Entity> = vec![];
 
         for &player in &self.players {
+            // bots steer themselves in update_bots
+            if self.bot_components.contains_key(player) {
+                continue;
+            }
             let mut connected = true;
 
             let mut input_temp = PlayerInputComponent::default();
@@ -325,15 +447,11 @@ impl ECS {
             }
 
             // once all inputs have been aggregated for this player
-            let camera = &mut self.player_camera_components[player];
-            camera.rot = UnitQuaternion::from_quaternion(Quaternion::new(
+            self.player_camera_components[player].look(UnitQuaternion::from_quaternion(Quaternion::new(
                 input_temp.camera_qw,
                 input_temp.camera_qx,
                 input_temp.camera_qy,
-                input_temp.camera_qz));
-            camera.camera_front = camera.rot * vector![0.0,0.0,-1.0];
-            camera.camera_right = camera.rot * vector![1.0,0.0,0.0];
-            camera.camera_up = camera.rot * vector![0.0,1.0,0.0];
+                input_temp.camera_qz)));
             self.player_input_components[player] = input_temp;
         }
 
@@ -355,8 +473,12 @@ impl ECS {
         let j = bitcode::serialize(&lobby_ecs).expect("Lobby ECS serialization error");
         let size = j.len() as u32 + 4;
         for &player in &self.players {
+            let network = match self.network_components.get_mut(player) {
+                Some(network) => network,
+                None => continue,
+            };
             let message = [u32::to_be_bytes(size).to_vec(), j.clone()].concat();
-            match self.network_components[player].stream.write(&message) {
+            match network.stream.write(&message) {
                 Ok(_) => (),
                 Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => (),
                 Err(e) => {
@@ -372,6 +494,32 @@ impl ECS {
         }
     }
 
+    /**
+     * Send the end-of-round results to every connected client
+     *
+     * @param   results: placement and stats of each player
+     */
+    pub fn send_round_results(&mut self, results: &RoundResults) {
+        let j = bitcode::serialize(results).expect("Round results serialization error");
+        let size = j.len() as u32 + 4;
+        for &player in &self.players {
+            match self.network_components.get_mut(player) {
+                Some(network) if network.connected => {
+                    let message = [u32::to_be_bytes(size).to_vec(), j.clone()].concat();
+                    match network.stream.write(&message) {
+                        Ok(_) => (),
+                        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => (),
+                        Err(e) => {
+                            eprintln!("Error sending round results to client \"{}\": {:?}", self.name_components[player], e);
+                            network.connected = false;
+                        }
+                    }
+                },
+                _ => ()
+            }
+        }
+    }
+
     /**
      * Remove a player from ECS
      * 
@@ -398,15 +546,25 @@ impl ECS {
         self.player_input_components.remove(player);
         self.position_components.remove(player);
         self.player_weapon_components.remove(player);
+        self.player_fuel_components.remove(player);
+        self.player_dash_components.remove(player);
         self.model_components.remove(player);
         self.physics_components.remove(player);
         self.network_components.remove(player);
+        self.bot_components.remove(player);
         self.velocity_components.remove(player);
         self.player_health_components.remove(player);
         self.player_camera_components.remove(player);
         self.player_lasso_components.remove(player);
         self.player_lasso_phys_components.remove(player);
         self.player_lasso_thrown_components.remove(player);
+        self.lasso_cut_timers.remove(player);
+        self.last_attacker_components.remove(player);
+        self.damage_history.remove(player);
+        self.impact_cooldowns.remove(player);
+        self.team_components.remove(player);
+        self.account_names.remove(player);
+        self.match_scores.remove(player);
         if self.ready_players.contains_key(player) {
             self.ready_players.remove(player);
         }
@@ -417,6 +575,7 @@ impl ECS {
         self.active_players = self.players.len() as u8;
 
         self.moving.remove(player);
+        self.dampening.remove(player);
     }
 
     /**
@@ -436,6 +595,11 @@ impl ECS {
         curr.ctrl_pressed |= value.ctrl_pressed;
         curr.reset_pressed |= value.reset_pressed;
         curr.r_pressed |= value.r_pressed;
+        curr.q_pressed |= value.q_pressed;
+        curr.e_pressed |= value.e_pressed;
+        curr.f_pressed |= value.f_pressed;
+        curr.v_pressed |= value.v_pressed;
+        curr.x_pressed |= value.x_pressed;
         curr.camera_qx = value.camera_qx;
         curr.camera_qy = value.camera_qy;
         curr.camera_qz = value.camera_qz;
@@ -448,22 +612,13 @@ impl ECS {
     pub fn update_clients(&mut self) {
         let mut disconnected_players: Vec<Entity> = vec![];
 
-        // game ends if there's 1 active player left
-        if self.active_players <= 1 {
-            if self.eor_countdown == 0 {
-                self.game_ended = true;
-            } else {
-                self.eor_countdown -= 1;
-            }
-        }
-
         let client_ecs = self.client_ecs();
         let j = bitcode::serialize(&client_ecs).expect("Client ECS serialization error");
         let size = j.len() as u32 + 4;
         for &player in &self.players {
-            if self.network_components[player].connected {
+            if let Some(network) = self.network_components.get_mut(player).filter(|network| network.connected) {
                 let message = [u32::to_be_bytes(size).to_vec(), j.clone()].concat();
-                match self.network_components[player].stream.write(&message) {
+                match network.stream.write(&message) {
                     Ok(_) => (),
                     Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => eprintln!("WouldBlock error while sending {size} bytes: {e}"),
                     Err(e) => {
@@ -489,17 +644,34 @@ impl ECS {
             name_components: self.name_components.clone(),
             position_components: self.position_components.clone(),
             weapon_components: self.player_weapon_components.clone(),
+            fuel_components: self.player_fuel_components.clone(),
+            dash_components: self.player_dash_components.clone(),
             model_components: self.model_components.clone(),
             health_components: self.player_health_components.clone(),
             particle_components: self.particle_components.clone(),
             player_lasso_components: self.player_lasso_components.clone(),
             event_components: self.event_components.clone(),
             velocity_components: self.velocity_components.clone(),
+            team_components: self.team_components.clone(),
+            dampening: self.dampening.clone(),
+            flag_components: self.flag_components.clone(),
             players: self.players.clone(),
             ids: self.ids.clone(),
             events: self.events.clone(),
             renderables: self.renderables.clone(),
+            standings: self.standings.clone(),
+            match_scores: self.match_scores.clone(),
+            round_number: self.round_number,
+            rounds_per_match: self.config.rounds_per_match,
+            dash_cooldown_ticks: self.config.dash.cooldown_ticks,
+            spawn_protection_ticks: self.config.spawn.protection_ticks,
             active_players: self.active_players.clone(),
+            round_over: self.round_over,
+            match_over: self.match_over,
+            zone: self.zone.clone(),
+            hills: self.hills.clone(),
+            force_sources: self.force_sources.iter().map(|source| source.field.clone()).collect(),
+            nebulae: self.nebulae.iter().map(|nebula| nebula.cloud.clone()).collect(),
             game_ended: self.game_ended,
         }
     }
@@ -514,13 +686,44 @@ impl ECS {
             name_components: self.name_components.clone(),
             position_components: self.position_components.clone(),
             ready_players: self.ready_players.clone(),
+            team_components: self.team_components.clone(),
             players: self.players.clone(),
             ids: self.ids.clone(),
             sky: self.sky.clone(),
+            num_teams: self.config.team_count(),
+            leaderboard: self.history.leaderboard(self.config.history.leaderboard_size),
+            records: self.players.iter().filter_map(|&player| self.history.entry(&self.account_name(player)).map(|entry| (player, entry))).collect(),
             start_game: start_game,
         }
     }
 
+    /**
+     * Add a player to the lobby, ready to be given a connection or a bot brain
+     *
+     * @param   name: display name
+     *
+     * @return  the new player key
+     */
+    pub fn join_player(&mut self, name: String) -> Entity {
+        let player = self.new_player(name);
+        self.player_health_components.insert(player, PlayerHealthComponent::default());
+        self.moving.insert(player, false);
+        self.dampening.insert(player, false);
+        if self.config.team_count() > 0 {
+            let team = self.smallest_team();
+            self.team_components.insert(player, team);
+        }
+        self.active_players += 1;
+        player
+    }
+
+    /**
+     * Check if a player is still in the game, bots never disconnect
+     */
+    pub fn connected(&self, player: Entity) -> bool {
+        self.bot_components.contains_key(player) || self.network_components.get(player).map_or(false, |network| network.connected)
+    }
+
     /**
      * Creates a new player
      *
@@ -538,6 +741,9 @@ impl ECS {
         self.model_components.insert(player, ModelComponent { modelname: "characterPink".to_string(), scale: 1.0, border: false });
         self.player_input_components.insert(player, PlayerInputComponent::default());
         self.player_weapon_components.insert(player, PlayerWeaponComponent::default());
+        self.player_fuel_components.insert(player, PlayerFuelComponent::default());
+        self.player_dash_components.insert(player, PlayerDashComponent::default());
+        self.damage_history.insert(player, vec![]);
         self.player_camera_components.insert(player, PlayerCameraComponent::default());
         self.position_components.insert(player, PositionComponent::default());
         self.velocity_components.insert(player, VelocityComponent::default());
@@ -548,14 +754,27 @@ impl ECS {
         let mass = collider.mass_properties().mass();
         let principal_inertia = collider.mass_properties().principal_inertia();
         collider.set_mass_properties(MassProperties::new(local_com, mass, principal_inertia * 10.0));
+        collider.set_active_events(ActiveEvents::CONTACT_FORCE_EVENTS);
+        collider.set_contact_force_event_threshold(self.impact_force_threshold(mass));
         let collider_handle = self.collider_set.insert_with_parent(collider, handle, &mut self.rigid_body_set);
+        self.collider_set.insert_with_parent(ECS::head_collider(player, index), handle, &mut self.rigid_body_set);
         self.physics_components.insert(player,PhysicsComponent{handle, collider_handle});
         player
     }
 
+    /**
+     * Build a massless head sensor for a player so shots can tell head from body. It is
+     * narrower than the capsule and sits in its top cap, so only shots at the top of the
+     * player or from above are hit on the head before the body
+     */
+    fn head_collider(player: Entity, index: usize) -> Collider {
+        ColliderBuilder::ball(0.25).translation(vector![0.0, 0.75, 0.0]).density(0.0).sensor(true)
+            .user_data(player.data().as_ffi() as u128).collision_groups(InteractionGroups::new(((1 as u32) << (index + 1)).into(),Group::all())).build()
+    }
+
     pub fn spawn_prop(&mut self, name: String, modelname: String, pos_x: f32, pos_y: f32, pos_z: f32,
         qx: f32, qy: f32, qz: f32, qw: f32, dynamic: bool, shape: SharedShape, scale: f32, density: f32, restitution: f32, border: bool,
-        linvel: Vector3<f32>, angvel: Vector3<f32>) {
+        linvel: Vector3<f32>, angvel: Vector3<f32>) -> Entity {
             let entity = self.name_components.insert(name);
             let rot = UnitQuaternion::from_quaternion(Quaternion::new(qw,qx,qy,qz));
             self.position_components.insert(
@@ -597,20 +816,30 @@ impl ECS {
             ).build();
             let collider_handle = self.collider_set.insert_with_parent(collider, handle, &mut self.rigid_body_set);
             self.physics_components.insert(entity, PhysicsComponent { handle, collider_handle });
+            entity
+    }
 
+    /**
+     * Name a player's match history is kept under, their display name if the client never sent one
+     */
+    pub fn account_name(&self, player: Entity) -> String {
+        self.account_names.get(player).unwrap_or(&self.name_components[player]).clone()
     }
 
     pub fn update_player_models(&mut self) {
         let names = ["Il Rosso", "Il Blu", "Il Giallo", "Il Verde"];
         let models = ["characterPink", "characterBlue", "characterYellow", "characterGreen"];
-        for (index, &player) in self.players.iter().enumerate() {
+        // everyone spawns at once, so only players placed earlier in the loop are threats
+        let mut placed: Vec<(Entity, Vector3<f32>)> = vec![];
+        for (index, player) in self.players.clone().into_iter().enumerate() {
             self.name_components[player] = names[index % names.len()].to_string();
             self.model_components[player].modelname = models[index % names.len()].to_string();
-            if self.spawnpoints.is_empty() {
-                eprintln!("Ran out of player spawnpoints, reusing");
-                init_player_spawns(&mut self.spawnpoints);
-            }
-            let player_pos = get_rand_from_vec(&mut self.spawnpoints);
+            let threats: Vec<Vector3<f32>> = placed.iter()
+                .filter(|&&(other, _)| !ECS::same_team(&self.team_components, player, other))
+                .map(|&(_, pos)| pos).collect();
+            let player_pos = self.take_spawnpoint(player, &threats);
+            placed.push((player, player_pos.translation.vector));
+            self.player_health_components[player].shield_ticks = self.config.spawn.protection_ticks;
             self.position_components[player] = PositionComponent{
                 x: player_pos.translation.x,
                 y: player_pos.translation.y,
@@ -625,6 +854,143 @@ impl ECS {
         }
     }
 
+    /**
+     * Remove and return the safest spawnpoint usable by a player, reserved team spawns are
+     * only handed out to members of that team
+     *
+     * @param   player key
+     * @param   threats: positions of opponents to spawn away from, a random spawn is used if empty
+     *
+     * @return  the spawn position
+     */
+    pub fn take_spawnpoint(&mut self, player: Entity, threats: &[Vector3<f32>]) -> Isometry3<f32> {
+        let team = self.team_components.get(player).copied();
+        let usable = |spawn: &PlayerSpawn| spawn.team.is_none() || team.is_none() || spawn.team == team;
+        if !self.spawnpoints.iter().any(usable) {
+            eprintln!("Ran out of player spawnpoints, reusing");
+            init_player_spawns(&mut self.spawnpoints);
+        }
+        let mut candidates: Vec<usize> = (0..self.spawnpoints.len()).filter(|&i| usable(&self.spawnpoints[i])).collect();
+        if candidates.is_empty() {
+            eprintln!("No spawnpoints for team {:?}, using any", team);
+            candidates = (0..self.spawnpoints.len()).collect();
+        }
+        let index = if threats.is_empty() {
+            get_rand_from_vec(&mut candidates)
+        } else {
+            self.query_pipeline.update(&self.rigid_body_set, &self.collider_set);
+            let score = |&i: &usize| self.spawn_score(&self.spawnpoints[i].pos.translation.vector, threats);
+            candidates.into_iter().max_by(|a, b| score(a).total_cmp(&score(b))).unwrap()
+        };
+        self.spawnpoints.swap_remove(index).pos
+    }
+
+    /**
+     * Rate how safe a spawn position is, the distance to the nearest threat with
+     * threats that have line of sight to it counting as closer
+     */
+    fn spawn_score(&self, spawn: &Vector3<f32>, threats: &[Vector3<f32>]) -> f32 {
+        threats.iter().map(|threat| {
+            let offset = spawn - threat;
+            let dist = offset.norm();
+            if dist == 0.0 {
+                return 0.0;
+            }
+            // only static geometry counts as cover, players and props drift out of the way
+            let ray = Ray::new(Point::from(*threat), offset / dist);
+            let filter = QueryFilter::exclude_dynamic().exclude_sensors();
+            let visible = self.query_pipeline.cast_ray(&self.rigid_body_set, &self.collider_set, &ray, dist, true, filter).is_none();
+            if visible { dist * self.config.spawn.visible_penalty } else { dist }
+        }).fold(f32::MAX, f32::min)
+    }
+
+    /**
+     * Positions of living opponents a player should spawn away from
+     */
+    pub fn spawn_threats(&self, player: Entity) -> Vec<Vector3<f32>> {
+        self.players.iter()
+            .filter(|&&other| other != player && self.player_health_components[other].alive && !ECS::same_team(&self.team_components, player, other))
+            .map(|&other| *self.rigid_body_set[self.physics_components[other].handle].translation())
+            .collect()
+    }
+
+    /**
+     * Team with the fewest players, used to place newly connected players
+     */
+    fn smallest_team(&self) -> u8 {
+        (0..self.config.team_count()).min_by_key(|&team| {
+            self.team_components.values().filter(|&&t| t == team).count()
+        }).unwrap_or(0)
+    }
+
+    /**
+     * Move players from the largest to the smallest team until team sizes differ by at most one
+     */
+    pub fn balance_teams(&mut self) {
+        let num_teams = self.config.team_count();
+        if num_teams == 0 || !self.config.auto_balance {
+            return;
+        }
+        loop {
+            let team_size = |team: u8| self.team_components.values().filter(|&&t| t == team).count();
+            let largest = (0..num_teams).max_by_key(|&team| team_size(team)).unwrap();
+            let smallest = (0..num_teams).min_by_key(|&team| team_size(team)).unwrap();
+            if team_size(largest) <= team_size(smallest) + 1 {
+                break;
+            }
+            // move the most recently joined member of the largest team
+            let &player = self.players.iter().rev().find(|&&p| self.team_components.get(p) == Some(&largest)).unwrap();
+            self.team_components[player] = smallest;
+        }
+    }
+
+    /**
+     * Check if two players are on the same team, always false in free-for-all modes
+     *
+     * @param   teams: team components to look the players up in
+     * @param   a, b: player keys
+     */
+    pub fn same_team(teams: &SecondaryMap<Entity, u8>, a: Entity, b: Entity) -> bool {
+        match (teams.get(a), teams.get(b)) {
+            (Some(team_a), Some(team_b)) => team_a == team_b,
+            _ => false,
+        }
+    }
+
+    /**
+     * Bring a dead player back to life at the spawnpoint safest from their opponents
+     *
+     * @param   player key
+     */
+    pub fn respawn_player(&mut self, player: Entity) {
+        let threats = self.spawn_threats(player);
+        let player_pos = self.take_spawnpoint(player, &threats);
+        self.position_components[player] = PositionComponent{
+            x: player_pos.translation.x,
+            y: player_pos.translation.y,
+            z: player_pos.translation.z,
+            qx: player_pos.rotation.i,
+            qy: player_pos.rotation.j,
+            qz: player_pos.rotation.k,
+            qw: player_pos.rotation.w,
+        };
+        self.player_health_components[player] = PlayerHealthComponent {
+            shield_ticks: self.config.spawn.protection_ticks,
+            ..PlayerHealthComponent::default()
+        };
+        self.player_weapon_components[player] = PlayerWeaponComponent::default();
+        self.player_fuel_components[player] = PlayerFuelComponent::default();
+        self.player_dash_components[player] = PlayerDashComponent::default();
+        self.dampening[player] = false;
+        self.player_input_components[player] = PlayerInputComponent::default();
+        let rigid_body = self.rigid_body_set.get_mut(self.physics_components[player].handle).unwrap();
+        rigid_body.set_locked_axes(LockedAxes::ROTATION_LOCKED, true);
+        rigid_body.set_position(player_pos, true);
+        rigid_body.set_linvel(Vector3::zeros(), true);
+        rigid_body.set_angvel(Vector3::zeros(), true);
+        self.active_players += 1;
+    }
+
     /**
      * Updates position components of all objects in the game
      */
@@ -651,9 +1017,15 @@ impl ECS {
      * detect if target is another player, update health components if necessary
      */
     pub fn player_fire(&mut self) {
+        let roped: Vec<Entity> = self.player_lasso_components.values().filter_map(|lasso| lasso.tethered).collect();
+        // damage is dealt once every player has fired, it needs the whole ECS
+        let mut hits: Vec<(Entity, Entity, u8, DamageSource)> = vec![];
+        let mut prop_hits: Vec<(Entity, Entity, u8)> = vec![];
         for &player in &self.players {
             let mut weapon = &mut self.player_weapon_components[player];
             let input = &self.player_input_components[player];
+            let health = &mut self.player_health_components[player];
+            health.shield_ticks = health.shield_ticks.saturating_sub(1);
             if weapon.cooldown > 0 {
                 weapon.cooldown -= 1;
                 if weapon.reloading && weapon.cooldown == 0 {
@@ -667,6 +1039,8 @@ impl ECS {
                 let position = &self.position_components[player];
                 let halfheight = 0.5;
                 let fire_point = point![position.x, position.y, position.z] + (self.player_camera_components[player].camera_up * halfheight);
+                // shooting gives up spawn protection
+                self.player_health_components[player].shield_ticks = 0;
 
                 // add fire event to server tick
                 let event_key = self.name_components.insert("fire_event".to_string());
@@ -687,12 +1061,18 @@ impl ECS {
                         let target_name = & self.name_components[target];
                         // println!("Hit target {}",target_name);
 
-                        let target_body = self.rigid_body_set.get_mut(self.physics_components[target].handle).unwrap();
-                        let target_start_vel = target_body.linvel();
+                        // players carry a body collider and a head sensor, anything but the body is the head
+                        let region = if self.players.contains(&target) && target_collider_handle != self.physics_components[target].collider_handle {
+                            HitRegion::Head
+                        } else {
+                            HitRegion::Body
+                        };
+
+                        let target_start_vel = *self.rigid_body_set[self.physics_components[target].handle].linvel();
 
                         let event_key = self.name_components.insert("hit_event".to_string());
                         self.events.push(event_key);
-                        self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::HitEvent{player, target, hit_x: hit_point.x, hit_y: hit_point.y, hit_z: hit_point.z}});
+                        self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::HitEvent{player, target, region, hit_x: hit_point.x, hit_y: hit_point.y, hit_z: hit_point.z}});
                         self.particle_components.insert(event_key, ParticleComponent {
                             x: hit_point.x,
                             y: hit_point.y,
@@ -706,23 +1086,21 @@ impl ECS {
                         });
 
                         // if target is a player, update its health component
-                        if self.players.contains(&target) && self.player_health_components[target].alive {
-                            self.player_health_components[target].health -= 1;
-                            self.player_health_components[player].hits += 1;
-
-                            if self.player_health_components[target].health == 0 {
-                                // handle player death
-                                let event_key = self.name_components.insert("death_event".to_string());
-                                self.events.push(event_key);
-                                self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::DeathEvent { player: target, killer: player }});
-                                self.player_health_components[target].alive = false;
-                                self.active_players -= 1;
-                                self.player_input_components[target] = PlayerInputComponent::default();
-                                target_body.set_locked_axes(LockedAxes::empty(), true);
-                            }
+                        let friendly = ECS::same_team(&self.team_components, player, target) && !self.config.friendly_fire;
+                        let shielded = self.players.contains(&target) && self.player_health_components[target].shield_ticks > 0;
+                        if self.players.contains(&target) && self.player_health_components[target].alive && !friendly && !shielded {
+                            let damage = self.config.weapon.damage_for(region);
+                            let source = if region == HitRegion::Head { DamageSource::Headshot } else { DamageSource::Shot };
+                            hits.push((target, player, damage, source));
+                        } else if self.prop_health.contains_key(target) {
+                            prop_hits.push((target, player, self.config.weapon.damage_for(HitRegion::Body)));
                         }
 
+                        let target_body = self.rigid_body_set.get_mut(self.physics_components[target].handle).unwrap();
                         target_body.apply_impulse_at_point(impulse, hit_point, true);
+                        if self.players.contains(&target) && !friendly {
+                            self.last_attacker_components.insert(target, LastAttackerComponent { attacker: player, ticks: self.config.impact.attribution_ticks });
+                        }
 
                     },
                     None => (),
@@ -733,7 +1111,7 @@ impl ECS {
                 // weapon cooldown is measured in ticks
                 weapon.cooldown = 30;
                 weapon.ammo -= 1;
-            } else if (input.lmb_clicked || (input.r_pressed && weapon.ammo < AMMO_COUNT)) && weapon.cooldown == 0 {
+            } else if (input.lmb_clicked || (input.r_pressed && weapon.ammo < AMMO_COUNT && !roped.contains(&player))) && weapon.cooldown == 0 {
                 weapon.cooldown = 120;
                 weapon.reloading = true;
 
@@ -742,6 +1120,15 @@ impl ECS {
                 self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::ReloadEvent{player}});
             }
         }
+        for (target, player, damage, source) in hits {
+            self.damage_player(target, player, damage, source);
+        }
+        for (prop, player, damage) in prop_hits {
+            // several shots can land on a prop in one tick, the first to break it removes it
+            if self.physics_components.contains_key(prop) {
+                self.damage_prop(prop, player, damage);
+            }
+        }
     }
 
     /**
@@ -751,14 +1138,30 @@ impl ECS {
         'players: for (index, &player) in self.players.iter().enumerate() {
             let halfheight = 0.5;
             let spawn_dist = 0.0;
-            let fire_vel = 150.0;
-            let impulse = 0.1;
-            let slack = 0.5;
-            let max_dist = 500.0;
+            let lasso = &self.config.lasso;
+            // rope joints limit each axis separately, so lengths are scaled down to per-axis limits
+            let min_limit = lasso.min_length / 3.0_f32.sqrt();
+            let max_limit = lasso.max_length / 3.0_f32.sqrt();
             let input = &self.player_input_components[player];
             if self.player_lasso_phys_components.contains_key(player) {
                 let lasso_phys = &mut self.player_lasso_phys_components[player];
-                if input.rmb_clicked && self.name_components.contains_key(lasso_phys.anchor) {
+
+                // a lassoed player cuts the rope by holding still for a reload
+                let mut cut = false;
+                if let Some(target) = self.player_lasso_components[player].tethered {
+                    match self.lasso_cut_timers.get(player).copied() {
+                        Some(0) => cut = true,
+                        Some(timer) => { self.lasso_cut_timers.insert(player, timer - 1); },
+                        None if self.player_input_components[target].r_pressed => { self.lasso_cut_timers.insert(player, lasso.cut_ticks); },
+                        None => (),
+                    }
+                }
+
+                // only loose props can be flung, not players or static scenery
+                let fling = input.f_pressed && self.player_lasso_components[player].tethered.is_none()
+                    && self.rigid_body_set.get(lasso_phys.anchor_handle).map_or(false, |body| body.is_dynamic());
+
+                if input.rmb_clicked && !cut && !fling && self.name_components.contains_key(lasso_phys.anchor) {
                     let position = &self.position_components[player];
                     let anchor: &mut RigidBody = self.rigid_body_set.get_mut(lasso_phys.anchor_handle).unwrap();
                     let anchor_point = anchor.position() * lasso_phys.anchor_point_local;
@@ -766,26 +1169,58 @@ impl ECS {
                     self.player_lasso_components[player].anchor_y = anchor_point.y;
                     self.player_lasso_components[player].anchor_z = anchor_point.z;
                     let dist = distance(&point![position.x, position.y, position.z],&anchor_point);
-                    let new_limit = dist / 3.0_f32.sqrt() + slack;
-                    let ropejoint = self.impulse_joint_set.get_mut(lasso_phys.joint_handle).unwrap();
-                    if new_limit < lasso_phys.limit {
-                        lasso_phys.limit = new_limit;
+                    let taut_limit = dist / 3.0_f32.sqrt() + lasso.slack;
+                    if input.q_pressed && !input.e_pressed {
+                        lasso_phys.limit -= lasso.reel_in_speed;
+                    } else if input.e_pressed && !input.q_pressed {
+                        lasso_phys.limit += lasso.reel_out_speed;
+                    } else if taut_limit < lasso_phys.limit {
+                        // take up slack so the rope stays taut
+                        lasso_phys.limit = taut_limit;
                     }
+                    lasso_phys.limit = lasso_phys.limit.clamp(min_limit, max_limit);
                     let lim = lasso_phys.limit;
+                    let ropejoint = self.impulse_joint_set.get_mut(lasso_phys.joint_handle).unwrap();
                     ropejoint.data.set_limits(JointAxis::X, [lim,lim]);
                     ropejoint.data.set_limits(JointAxis::Y, [lim,lim]);
                     ropejoint.data.set_limits(JointAxis::Z, [lim,lim]);
 
-                    let anchor = self.rigid_body_set.get_mut(self.player_lasso_phys_components[player].anchor_handle).unwrap();
-                    // let anchor_t = anchor.translation().clone();
-                    // TODO: calculate impulse based on mass of objects
-                    anchor.apply_impulse_at_point((vector![position.x, position.y, position.z]-vector![anchor_point.x, anchor_point.y, anchor_point.z]).normalize() * impulse, anchor_point, true);
+                    let to_anchor = (vector![anchor_point.x, anchor_point.y, anchor_point.z]-vector![position.x, position.y, position.z]).normalize();
+                    if input.q_pressed && !input.e_pressed {
+                        // TODO: calculate impulse based on mass of objects
+                        anchor.apply_impulse_at_point(-to_anchor * lasso.pull_impulse, anchor_point, true);
+                        let rigid_body = self.rigid_body_set.get_mut(self.physics_components[player].handle).unwrap();
+                        rigid_body.apply_impulse(to_anchor * lasso.pull_impulse, true);
+                    }
+
+                    // push the player further along their swing around the anchor
                     let rigid_body = self.rigid_body_set.get_mut(self.physics_components[player].handle).unwrap();
-                    rigid_body.apply_impulse((vector![anchor_point.x, anchor_point.y, anchor_point.z]-vector![position.x, position.y, position.z]).normalize() * impulse, true);
+                    let vel = *rigid_body.linvel();
+                    let tangent = vel - to_anchor * vel.dot(&to_anchor);
+                    if taut_limit >= lim && tangent.magnitude() > 0.1 {
+                        rigid_body.apply_impulse(tangent.normalize() * lasso.swing_boost, true);
+                    }
                 } else {
+                    // velocities are left alone so the player keeps the momentum of their swing
+                    let (anchor, anchor_handle) = (lasso_phys.anchor, lasso_phys.anchor_handle);
                     self.impulse_joint_set.remove(lasso_phys.joint_handle,true);
                     self.player_lasso_phys_components.remove(player);
                     self.player_lasso_components.remove(player);
+                    self.lasso_cut_timers.remove(player);
+
+                    // flung props keep the velocity they built up on the rope
+                    if fling {
+                        let fire_vec = self.player_camera_components[player].camera_front;
+                        let anchor_body = self.rigid_body_set.get_mut(anchor_handle).unwrap();
+                        let vel = *anchor_body.linvel() + fire_vec * lasso.fling_speed;
+                        anchor_body.set_linvel(vel, true);
+                        self.thrown_prop_components.insert(anchor, ThrownPropComponent { thrower: player, ticks: lasso.fling_ticks, prev_vel: vel });
+                    }
+
+                    // add lasso release event
+                    let event_key = self.name_components.insert("lasso_release_event".to_string());
+                    self.events.push(event_key);
+                    self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::LassoReleaseEvent{player}});
                 }
             } else if self.player_lasso_thrown_components.contains_key(player) {
                 let position = &self.position_components[player];
@@ -825,7 +1260,7 @@ impl ECS {
                                     continue 'players;
                                 }
                                 let dist = distance(&point![position.x, position.y, position.z],&hit_point);
-                                let limit = dist / 3.0_f32.sqrt() + slack;
+                                let limit = (dist / 3.0_f32.sqrt() + lasso.slack).clamp(min_limit, max_limit);
                                 let target_handle = &self.physics_components[target].handle;
                                 let target_body = self.rigid_body_set.get_mut(*target_handle).unwrap();
                                 let hit_point_local = target_body.position().inverse() * hit_point;
@@ -835,6 +1270,21 @@ impl ECS {
                                 self.player_lasso_components[player].anchor_x = hit_point.x;
                                 self.player_lasso_components[player].anchor_y = hit_point.y;
                                 self.player_lasso_components[player].anchor_z = hit_point.z;
+
+                                let target_type = if self.players.contains(&target) { LassoTarget::Player } else { LassoTarget::Prop };
+                                if target_type == LassoTarget::Player {
+                                    self.player_lasso_components[player].tethered = Some(target);
+                                    self.last_attacker_components.insert(target, LastAttackerComponent { attacker: player, ticks: self.config.impact.attribution_ticks });
+
+                                    // take part of the target's momentum and yank them towards the thrower
+                                    let target_body = self.rigid_body_set.get_mut(*target_handle).unwrap();
+                                    let stolen = *target_body.linvel() * lasso.momentum_steal;
+                                    let yank = (vector![position.x, position.y, position.z] - target_body.translation()).normalize() * lasso.yank_impulse;
+                                    target_body.set_linvel(*target_body.linvel() - stolen, true);
+                                    target_body.apply_impulse(yank, true);
+                                    let player_body = self.rigid_body_set.get_mut(*player_handle).unwrap();
+                                    player_body.set_linvel(*player_body.linvel() + stolen, true);
+                                }
                                 self.dynamics.remove(self.dynamics.iter().position(|x| *x == thrown.entity).expect("not found"));
                                 self.rigid_body_set.remove(thrown_phys.handle, &mut self.island_manager, &mut self.collider_set, &mut self.impulse_joint_set, &mut self.multibody_joint_set, true);
                                 self.name_components.remove(thrown.entity);
@@ -846,7 +1296,7 @@ impl ECS {
                                 // add lasso attach event
                                 let event_key = self.name_components.insert("lasso_attach_event".to_string());
                                 self.events.push(event_key);
-                                self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::LassoAttachEvent { target, hit_x: hit_point.x, hit_y: hit_point.y, hit_z: hit_point.z }});
+                                self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::LassoAttachEvent { player, target, target_type, hit_x: hit_point.x, hit_y: hit_point.y, hit_z: hit_point.z }});
 
                                 continue 'players;
                             }
@@ -854,7 +1304,7 @@ impl ECS {
                     }
                     let thrown_pos = &self.position_components[thrown.entity];
                     let dist = distance(&point![position.x, position.y, position.z],&point![thrown_pos.x, thrown_pos.y, thrown_pos.z]);
-                    if dist > max_dist {
+                    if dist > lasso.max_dist {
                         self.dynamics.remove(self.dynamics.iter().position(|x| *x == thrown.entity).expect("not found"));
                         self.rigid_body_set.remove(thrown_phys.handle, &mut self.island_manager, &mut self.collider_set, &mut self.impulse_joint_set, &mut self.multibody_joint_set, true);
                         self.name_components.remove(thrown.entity);
@@ -896,13 +1346,13 @@ impl ECS {
                 let player_body = self.rigid_body_set.get_mut(*player_handle).unwrap();
 
                 let thrown = self.name_components.insert("thrown lasso".to_string());
-                let thrown_body = RigidBodyBuilder::dynamic().position(Isometry3::from_parts(Translation3::from(point![position.x, position.y, position.z] + (self.player_camera_components[player].camera_up * halfheight) + (fire_vec * spawn_dist)), *player_body.rotation())).linvel(*player_body.linvel() + (fire_vec * fire_vel)).lock_rotations().ccd_enabled(true).can_sleep(false).build();
+                let thrown_body = RigidBodyBuilder::dynamic().position(Isometry3::from_parts(Translation3::from(point![position.x, position.y, position.z] + (self.player_camera_components[player].camera_up * halfheight) + (fire_vec * spawn_dist)), *player_body.rotation())).linvel(*player_body.linvel() + (fire_vec * lasso.fire_vel)).lock_rotations().ccd_enabled(true).can_sleep(false).build();
                 let thrown_handle = self.rigid_body_set.insert(thrown_body);
                 let thrown_collider = ColliderBuilder::ball(radius).user_data(thrown.data().as_ffi() as u128).collision_groups(InteractionGroups::new(Group::all(),(!((1 as u32) << (index + 1))).into())).build();
                 let thrown_collider_handle = self.collider_set.insert_with_parent(thrown_collider, thrown_handle, &mut self.rigid_body_set);
                 self.physics_components.insert(thrown, PhysicsComponent { handle: thrown_handle, collider_handle: thrown_collider_handle });
                 self.player_lasso_thrown_components.insert(player, PlayerLassoThrownComponent { entity: thrown });
-                self.player_lasso_components.insert(player, PlayerLassoComponent { anchor_x: position.x, anchor_y: position.y, anchor_z: position.z });
+                self.player_lasso_components.insert(player, PlayerLassoComponent { anchor_x: position.x, anchor_y: position.y, anchor_z: position.z, tethered: None });
                 self.position_components.insert(thrown, position.clone());
                 self.velocity_components.insert(thrown, VelocityComponent::default());
                 self.dynamics.push(thrown);
@@ -915,6 +1365,247 @@ impl ECS {
         }
     }
 
+    /**
+     * Shrink the play zone on schedule, push back and damage players who stay outside of it
+     */
+    pub fn update_zone(&mut self) {
+        if !self.config.zone.enabled {
+            return;
+        }
+        let [x, y, z] = self.config.zone.center;
+        self.zone = PlayZone { x, y, z, radius: self.config.zone.radius_at(self.zone_ticks) };
+        self.zone_ticks += 1;
+
+        let center = vector![x, y, z];
+        let push = self.config.zone.push;
+        let damage_ticks = self.config.zone.damage_ticks;
+        let mut damaged = vec![];
+        for &player in &self.players {
+            let position = &self.position_components[player];
+            let offset = vector![position.x, position.y, position.z] - center;
+            if !self.player_health_components[player].alive || offset.magnitude() <= self.zone.radius {
+                self.zone_damage_timers.remove(player);
+                continue;
+            }
+
+            let rigid_body = self.rigid_body_set.get_mut(self.physics_components[player].handle).unwrap();
+            rigid_body.apply_impulse(-offset.normalize() * push, true);
+
+            // players get a grace period before every point of damage
+            let timer = self.zone_damage_timers.get(player).copied().unwrap_or(damage_ticks);
+            if timer > 0 {
+                self.zone_damage_timers.insert(player, timer - 1);
+                continue;
+            }
+            self.zone_damage_timers.insert(player, damage_ticks);
+            damaged.push(player);
+        }
+
+        for player in damaged {
+            let event_key = self.name_components.insert("zone_damage_event".to_string());
+            self.events.push(event_key);
+            self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::ZoneDamageEvent { player }});
+            // the zone counts as the player killing themselves
+            self.damage_player(player, player, 1, DamageSource::Zone);
+        }
+    }
+
+    /**
+     * Damage players struck hard enough by props flung from a lasso, crediting the thrower
+     */
+    pub fn update_thrown_props(&mut self) {
+        let min_speed = self.config.lasso.fling_min_speed;
+        let mut hits = vec![];
+        let mut expired = vec![];
+        for (prop, thrown) in &mut self.thrown_prop_components {
+            if thrown.ticks == 0 || !self.physics_components.contains_key(prop) {
+                expired.push(prop);
+                continue;
+            }
+            thrown.ticks -= 1;
+
+            let prop_phys = &self.physics_components[prop];
+            'contacts: for contact_pair in self.narrow_phase.contacts_with(prop_phys.collider_handle) {
+                let other = if contact_pair.collider1 == prop_phys.collider_handle { contact_pair.collider2 } else { contact_pair.collider1 };
+                let target = DefaultKey::from(KeyData::from_ffi(self.collider_set[other].user_data as u64));
+                let friendly = ECS::same_team(&self.team_components, thrown.thrower, target) && !self.config.friendly_fire;
+                if !self.players.contains(&target) || target == thrown.thrower || friendly || !self.player_health_components[target].alive {
+                    continue;
+                }
+                let target_vel = *self.rigid_body_set[self.physics_components[target].handle].linvel();
+                if (thrown.prev_vel - target_vel).magnitude() < min_speed {
+                    continue;
+                }
+                for manifold in &contact_pair.manifolds {
+                    if manifold.data.solver_contacts.len() > 0 {
+                        hits.push((prop, thrown.thrower, target, manifold.data.solver_contacts[0].point));
+                        break 'contacts;
+                    }
+                }
+            }
+            thrown.prev_vel = *self.rigid_body_set[prop_phys.handle].linvel();
+        }
+
+        for prop in expired {
+            self.thrown_prop_components.remove(prop);
+        }
+        for (prop, thrower, target, hit_point) in hits {
+            // a flung prop only hurts the first player it hits
+            self.thrown_prop_components.remove(prop);
+            let event_key = self.name_components.insert("prop_hit_event".to_string());
+            self.events.push(event_key);
+            self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::PropHitEvent { player: thrower, target, hit_x: hit_point.x, hit_y: hit_point.y, hit_z: hit_point.z }});
+            self.last_attacker_components.insert(target, LastAttackerComponent { attacker: thrower, ticks: self.config.impact.attribution_ticks });
+            // the same collision also reports a contact force, which mustn't count as a second hit.
+            // handle_impacts ticks the cooldown down once before it looks at this step's contacts
+            self.impact_cooldowns.insert(target, self.config.impact.cooldown_ticks.saturating_add(1));
+            self.damage_player(target, thrower, self.config.lasso.fling_damage, DamageSource::Prop);
+        }
+    }
+
+    /**
+     * Contact force a player collider needs to report an impact at the feedback speed
+     *
+     * @param   mass: mass of the player's collider
+     */
+    fn impact_force_threshold(&self, mass: f32) -> f32 {
+        // contact forces are impulses spread over a tick
+        self.config.impact.feedback_speed * mass / (TICK_SPEED as f32 / 1000.0)
+    }
+
+    /**
+     * Turn the contact force events of the last physics step into impact feedback and damage,
+     * crediting kills to whoever last shot or lassoed the player
+     *
+     * @param   contact_force_events: events collected by the physics pipeline
+     */
+    pub fn handle_impacts(&mut self, contact_force_events: &Receiver<ContactForceEvent>) {
+        for (_, last_attacker) in &mut self.last_attacker_components {
+            last_attacker.ticks = last_attacker.ticks.saturating_sub(1);
+        }
+        self.last_attacker_components.retain(|_, last_attacker| last_attacker.ticks > 0);
+        let assist_ticks = self.config.assist_ticks;
+        for history in self.damage_history.values_mut() {
+            for record in history.iter_mut() {
+                record.ticks += 1;
+            }
+            history.retain(|record| record.ticks < assist_ticks);
+        }
+        for (_, cooldown) in &mut self.impact_cooldowns {
+            *cooldown = cooldown.saturating_sub(1);
+        }
+        self.impact_cooldowns.retain(|_, cooldown| *cooldown > 0);
+
+        let dt = TICK_SPEED as f32 / 1000.0;
+        while let Ok(event) = contact_force_events.try_recv() {
+            for collider_handle in [event.collider1, event.collider2] {
+                let player = match self.collider_set.get(collider_handle) {
+                    Some(collider) => DefaultKey::from(KeyData::from_ffi(collider.user_data as u64)),
+                    None => continue,
+                };
+                if !self.players.contains(&player) || !self.player_health_components[player].alive || self.impact_cooldowns.contains_key(player) {
+                    continue;
+                }
+
+                let mass = self.rigid_body_set[self.physics_components[player].handle].mass();
+                let magnitude = event.total_force_magnitude * dt / mass;
+                if magnitude < self.config.impact.feedback_speed {
+                    continue;
+                }
+                self.impact_cooldowns.insert(player, self.config.impact.cooldown_ticks);
+
+                let position = &self.position_components[player];
+                let mut impact_point = point![position.x, position.y, position.z];
+                if let Some(contact_pair) = self.narrow_phase.contact_pair(event.collider1, event.collider2) {
+                    for manifold in &contact_pair.manifolds {
+                        if manifold.data.solver_contacts.len() > 0 {
+                            impact_point = manifold.data.solver_contacts[0].point;
+                            break;
+                        }
+                    }
+                }
+                let event_key = self.name_components.insert("impact_event".to_string());
+                self.events.push(event_key);
+                self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::ImpactEvent { player, x: impact_point.x, y: impact_point.y, z: impact_point.z, magnitude }});
+
+                let impact = &self.config.impact;
+                if magnitude >= impact.damage_speed {
+                    let damage = 1 + ((magnitude - impact.damage_speed) / impact.speed_per_damage) as u8;
+                    let attacker = match self.last_attacker_components.get(player) {
+                        Some(last_attacker) if self.players.contains(&last_attacker.attacker) => last_attacker.attacker,
+                        _ => player,
+                    };
+                    self.damage_player(player, attacker, damage, DamageSource::Impact);
+                }
+            }
+        }
+    }
+
+    /**
+     * Deal damage to a player, killing them once their health runs out
+     *
+     * @param   target: player taking damage
+     * @param   attacker: player credited with the hit and the kill, the target itself for environmental damage
+     * @param   damage: health to take away
+     * @param   source: what dealt the damage, kept in the target's damage history
+     */
+    pub fn damage_player(&mut self, target: Entity, attacker: Entity, damage: u8, source: DamageSource) {
+        if !self.player_health_components[target].alive || self.player_health_components[target].shield_ticks > 0 {
+            return;
+        }
+        let health = &mut self.player_health_components[target];
+        health.health = health.health.saturating_sub(damage);
+        let dead = health.health == 0;
+        if let Some(history) = self.damage_history.get_mut(target) {
+            // steady damage like the zone's piles onto one record instead of one per tick
+            match history.last_mut() {
+                Some(last) if last.attacker == attacker && last.source == source => {
+                    last.damage = last.damage.saturating_add(damage);
+                    last.ticks = 0;
+                },
+                _ => history.push(DamageRecord { attacker, source, damage, ticks: 0 }),
+            }
+        }
+
+        if dead {
+            let assists = self.assists(target, attacker);
+            if let Some(history) = self.damage_history.get_mut(target) {
+                history.clear();
+            }
+            let event_key = self.name_components.insert("death_event".to_string());
+            self.events.push(event_key);
+            self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::DeathEvent { player: target, killer: attacker, cause: source, assists }});
+            self.player_health_components[target].alive = false;
+            self.active_players -= 1;
+            self.player_input_components[target] = PlayerInputComponent::default();
+            let rigid_body = self.rigid_body_set.get_mut(self.physics_components[target].handle).unwrap();
+            rigid_body.set_locked_axes(LockedAxes::empty(), true);
+        }
+    }
+
+    /**
+     * Opponents other than the killer who damaged a player within the assist window
+     */
+    fn assists(&self, player: Entity, killer: Entity) -> Vec<Entity> {
+        let mut assists: Vec<(Entity, u16)> = vec![];
+        if let Some(history) = self.damage_history.get(player) {
+            for record in history {
+                let attacker = record.attacker;
+                if attacker == player || attacker == killer || !self.players.contains(&attacker)
+                    || ECS::same_team(&self.team_components, player, attacker) {
+                    continue;
+                }
+                match assists.iter_mut().find(|(assist, _)| *assist == attacker) {
+                    Some((_, total)) => *total += record.damage as u16,
+                    None => assists.push((attacker, record.damage as u16)),
+                }
+            }
+        }
+        // biggest contributors first
+        assists.sort_by_key(|&(_, total)| std::cmp::Reverse(total));
+        assists.into_iter().map(|(assist, _)| assist).collect()
+    }
+
     /**
      * TODO: add description
      */
@@ -929,8 +1620,30 @@ impl ECS {
             let rigid_body = self.rigid_body_set.get_mut(self.physics_components[player].handle).unwrap();
             rigid_body.set_rotation(camera.rot, true);
 
+            // each axis thrusts only when exactly one of its two keys is held
+            let thrust_front = input.w_pressed as i8 - input.s_pressed as i8;
+            let thrust_right = input.d_pressed as i8 - input.a_pressed as i8;
+            let thrust_up = input.shift_pressed as i8 - input.ctrl_pressed as i8;
+            let axes = (thrust_front != 0) as u8 + (thrust_right != 0) as u8 + (thrust_up != 0) as u8;
+
+            // burn fuel while thrusting, regenerate it after a short rest
+            let fuel_config = &self.config.fuel;
+            let fuel = &mut self.player_fuel_components[player];
+            let mut thrusting = axes > 0;
+            if fuel_config.enabled {
+                if thrusting {
+                    thrusting = fuel.fuel > 0.0;
+                    fuel.fuel = (fuel.fuel - fuel_config.drain_per_tick * axes as f32).max(0.0);
+                    fuel.regen_cooldown = fuel_config.regen_delay_ticks;
+                } else if fuel.regen_cooldown > 0 {
+                    fuel.regen_cooldown -= 1;
+                } else {
+                    fuel.fuel = (fuel.fuel + fuel_config.regen_per_tick).min(1.0);
+                }
+            }
+
             // if movement started
-            if !self.moving[player] && (input.w_pressed || input.a_pressed || input.s_pressed || input.d_pressed || input.shift_pressed || input.ctrl_pressed) {
+            if !self.moving[player] && thrusting {
                 // println!("Start movement");
                 self.moving[player] = true;
                 // add start movement event to server tick
@@ -938,7 +1651,7 @@ impl ECS {
                 self.events.push(event_key);
                 self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::StartMoveEvent { player }});
             // else if movement stopped
-            } else if self.moving[player] && !(input.w_pressed || input.a_pressed || input.s_pressed || input.d_pressed || input.shift_pressed || input.ctrl_pressed) {
+            } else if self.moving[player] && !thrusting {
                 // println!("Stop movement");
                 self.moving[player] = false;
                 // add end movement event to server tick
@@ -947,28 +1660,95 @@ impl ECS {
                 self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::StopMoveEvent { player }});
             }
 
-            if input.w_pressed && !input.s_pressed {
-                rigid_body.apply_impulse(impulse * camera.camera_front, true);
+            if thrusting {
+                rigid_body.apply_impulse(impulse * thrust_front as f32 * camera.camera_front, true);
+                rigid_body.apply_impulse(impulse * thrust_right as f32 * camera.camera_right, true);
+                rigid_body.apply_impulse(impulse * thrust_up as f32 * camera.camera_up, true);
             }
-            if input.s_pressed && !input.w_pressed {
-                rigid_body.apply_impulse(-impulse * camera.camera_front, true);
+            if input.reset_pressed {
+                rigid_body.set_translation(Vector3::zeros(), true);
+                rigid_body.set_linvel(Vector3::zeros(), true);
             }
-            if input.a_pressed && !input.d_pressed {
-                rigid_body.apply_impulse(-impulse * camera.camera_right, true);
+        }
+    }
+
+    /**
+     * Slow down players holding the brake, and those drifting idle when auto-stabilize is on
+     */
+    pub fn player_brake(&mut self) {
+        let config = &self.config.brake;
+        for &player in &self.players {
+            self.dampening[player] = false;
+            if !config.enabled || !self.player_health_components[player].alive {
+                continue;
             }
-            if input.d_pressed && !input.a_pressed {
-                rigid_body.apply_impulse(impulse * camera.camera_right, true);
+
+            let mut damping = 0.0;
+            if self.player_input_components[player].x_pressed {
+                let fuel = &mut self.player_fuel_components[player];
+                if !self.config.fuel.enabled {
+                    damping = config.damping;
+                } else if fuel.fuel > 0.0 {
+                    damping = config.damping;
+                    fuel.fuel = (fuel.fuel - config.fuel_per_tick).max(0.0);
+                    fuel.regen_cooldown = self.config.fuel.regen_delay_ticks;
+                }
+            } else if config.auto_stabilize && !self.moving[player] {
+                damping = config.stabilize_damping;
             }
-            if input.shift_pressed && !input.ctrl_pressed {
-                rigid_body.apply_impulse(impulse * camera.camera_up, true);
+
+            let rigid_body = self.rigid_body_set.get_mut(self.physics_components[player].handle).unwrap();
+            let speed = rigid_body.linvel().norm();
+            if damping == 0.0 || speed == 0.0 {
+                continue;
             }
-            if input.ctrl_pressed && !input.shift_pressed {
-                rigid_body.apply_impulse(-impulse * camera.camera_up, true);
+            // oppose the current velocity, capped so high speeds bleed off gradually
+            let decel = (speed * damping).min(config.max_decel);
+            let impulse = -rigid_body.linvel() / speed * decel * rigid_body.mass();
+            rigid_body.apply_impulse(impulse, true);
+            self.dampening[player] = true;
+        }
+    }
+
+    /**
+     * Dash in the direction the player is thrusting, or where they are looking if they aren't
+     */
+    pub fn player_dash(&mut self) {
+        for &player in &self.players {
+            let dash = &mut self.player_dash_components[player];
+            if dash.cooldown > 0 {
+                dash.cooldown -= 1;
+                continue;
             }
-            if input.reset_pressed {
-                rigid_body.set_translation(Vector3::zeros(), true);
-                rigid_body.set_linvel(Vector3::zeros(), true);
+            let input = &self.player_input_components[player];
+            if !self.config.dash.enabled || !input.v_pressed || !self.player_health_components[player].alive {
+                continue;
+            }
+            let fuel = &mut self.player_fuel_components[player];
+            if self.config.fuel.enabled {
+                if fuel.fuel < self.config.dash.fuel_cost {
+                    continue;
+                }
+                fuel.fuel -= self.config.dash.fuel_cost;
+                fuel.regen_cooldown = self.config.fuel.regen_delay_ticks;
             }
+            dash.cooldown = self.config.dash.cooldown_ticks;
+
+            let camera = &self.player_camera_components[player];
+            let mut dir = (input.w_pressed as i8 - input.s_pressed as i8) as f32 * camera.camera_front
+                + (input.d_pressed as i8 - input.a_pressed as i8) as f32 * camera.camera_right
+                + (input.shift_pressed as i8 - input.ctrl_pressed as i8) as f32 * camera.camera_up;
+            if dir.norm() == 0.0 {
+                dir = camera.camera_front;
+            }
+            let dir = dir.normalize();
+
+            let rigid_body = self.rigid_body_set.get_mut(self.physics_components[player].handle).unwrap();
+            rigid_body.apply_impulse(self.config.dash.impulse * dir, true);
+
+            let event_key = self.name_components.insert("dash_event".to_string());
+            self.events.push(event_key);
+            self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::DashEvent { player, dir_x: dir.x, dir_y: dir.y, dir_z: dir.z }});
         }
     }
 
@@ -981,7 +1761,10 @@ impl ECS {
     pub fn check_ready_updates(&mut self){
         // check each connection for ready updates
         for &player in &self.players {
-            let mut stream = &self.network_components[player].stream;
+            let mut stream = match self.network_components.get(player) {
+                Some(network) => &network.stream,
+                None => continue,
+            };
             let mut size_buf = [0 as u8; 4];
             match stream.peek(&mut size_buf) {
                 Ok(4) => {
@@ -995,6 +1778,18 @@ impl ECS {
                             if ecs.ready {
                                 self.ready_players.insert(player, ecs.ready);
                             }
+                            if let Some(name) = ecs.name {
+                                let name: String = name.trim().chars().take(MAX_NAME_LENGTH).collect();
+                                if !name.is_empty() {
+                                    self.account_names.insert(player, name);
+                                }
+                            }
+                            match ecs.team {
+                                Some(team) if team < self.config.team_count() => {
+                                    self.team_components.insert(player, team);
+                                },
+                                _ => ()
+                            }
                         }
                         _ => ()
                     }
@@ -1017,8 +1812,12 @@ impl ECS {
             self.player_health_components[player].health = 0;
             self.active_players -= 1;
 
+            // leaving mid-fight still counts as a kill for whoever was winning it
+            let killer = self.damage_history.get(player)
+                .and_then(|history| history.iter().rev().find(|record| record.attacker != player && self.players.contains(&record.attacker)))
+                .map(|record| record.attacker);
             let event_key = self.name_components.insert("disconnect_event".to_string());
-            self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::DisconnectEvent { player: player }});
+            self.event_components.insert(event_key, EventComponent{lifetime:EVENT_LIFETIME, event_type:EventType::DisconnectEvent { player, killer }});
             self.events.push(event_key);
         }
     }
@@ -0,0 +1,209 @@
+use nalgebra::Vector3;
+use rapier3d::prelude::*;
+use slotmap::DefaultKey;
+
+use shared::shared_components::*;
+use crate::ecs::{ECS, EVENT_LIFETIME};
+use crate::server_config::GameModeKind;
+
+type Entity = DefaultKey;
+
+const FLAG_RADIUS: f32 = 0.5;
+// keeps loose flags from drifting off into space
+const FLAG_DAMPING: f32 = 1.0;
+// carried flags float this far above their carrier
+const CARRY_OFFSET: f32 = 1.5;
+
+/**
+ * Capture the flag: each team has a flag at its base that the other teams try to bring home,
+ * either by touching it and carrying it or by dragging it there on a lasso
+ */
+impl ECS {
+    /**
+     * Place a flag for every team in the middle of its spawn points, only in capture the flag
+     */
+    pub fn spawn_flags(&mut self) {
+        if self.config.game_mode != GameModeKind::CaptureTheFlag {
+            return;
+        }
+        for team in 0..self.config.team_count() {
+            let spawns: Vec<Vector3<f32>> = self.spawnpoints.iter()
+                .filter(|spawn| spawn.team == Some(team))
+                .map(|spawn| spawn.pos.translation.vector)
+                .collect();
+            if spawns.is_empty() {
+                eprintln!("No spawn points for team {team}, it will have no flag");
+                continue;
+            }
+            let home = spawns.iter().sum::<Vector3<f32>>() / spawns.len() as f32;
+            let flag = self.spawn_prop(format!("flag {team}"), "cube".to_string(), home.x, home.y, home.z,
+                0.0, 0.0, 0.0, 1.0, true, SharedShape::ball(FLAG_RADIUS), FLAG_RADIUS, 1.0, 0.3, false,
+                Vector3::zeros(), Vector3::zeros());
+            self.rigid_body_set[self.physics_components[flag].handle].set_linear_damping(FLAG_DAMPING);
+            self.flag_components.insert(flag, FlagComponent {
+                team,
+                state: FlagState::Home,
+                carrier: None,
+                home_x: home.x,
+                home_y: home.y,
+                home_z: home.z,
+            });
+        }
+    }
+
+    /**
+     * Move carried flags along with their carriers, then hand out pickups, returns and captures
+     */
+    pub fn update_flags(&mut self) {
+        let ctf = self.config.ctf.clone();
+        let flags: Vec<Entity> = self.flag_components.keys().collect();
+        for flag in flags {
+            let handle = self.physics_components[flag].handle;
+            let component = self.flag_components[flag].clone();
+            let home = Vector3::new(component.home_x, component.home_y, component.home_z);
+
+            if let Some(carrier) = component.carrier {
+                // carriers who die or leave drop the flag where they are
+                if !self.players.contains(&carrier) || !self.player_health_components[carrier].alive {
+                    self.drop_flag(flag, carrier);
+                    continue;
+                }
+                let carrier_body = &self.rigid_body_set[self.physics_components[carrier].handle];
+                let (pos, vel) = (*carrier_body.translation(), *carrier_body.linvel());
+                let up = self.player_camera_components[carrier].camera_up;
+                let body = &mut self.rigid_body_set[handle];
+                body.set_translation(pos + up * CARRY_OFFSET, true);
+                body.set_linvel(vel, true);
+                if self.at_own_base(carrier, &pos, ctf.capture_radius) {
+                    self.capture_flag(flag, carrier);
+                }
+                continue;
+            }
+
+            let pos = *self.rigid_body_set[handle].translation();
+            let dragger = self.lasso_owner(flag);
+            if component.state == FlagState::Home && (pos - home).norm() > ctf.home_radius {
+                match dragger {
+                    // lassoing a flag off its base takes it
+                    Some(player) if self.team_components.get(player) != Some(&component.team) => {
+                        self.flag_components[flag].state = FlagState::Dropped;
+                        self.flag_return_timers.insert(flag, ctf.return_ticks);
+                        self.flag_event("flag_taken_event", EventType::FlagTakenEvent { flag, player });
+                    },
+                    // anything else that knocked it off is undone
+                    _ => self.reset_flag(flag),
+                }
+                continue;
+            }
+
+            let toucher = self.players.iter().copied()
+                .filter(|&player| self.player_health_components[player].alive)
+                .map(|player| (player, (self.rigid_body_set[self.physics_components[player].handle].translation() - pos).norm()))
+                .filter(|&(_, dist)| dist < ctf.pickup_radius)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(player, _)| player);
+            match toucher {
+                Some(player) if self.team_components.get(player) == Some(&component.team) => {
+                    if component.state == FlagState::Dropped {
+                        self.return_flag(flag, Some(player));
+                    }
+                    continue;
+                },
+                Some(player) if !self.carrying(player) => {
+                    self.take_flag(flag, player);
+                    continue;
+                },
+                _ => (),
+            }
+
+            if component.state == FlagState::Dropped {
+                // dragging an enemy flag home on a lasso scores too
+                if let Some(player) = dragger {
+                    if self.team_components.get(player) != Some(&component.team) && self.at_own_base(player, &pos, ctf.capture_radius) {
+                        self.capture_flag(flag, player);
+                        continue;
+                    }
+                }
+                match self.flag_return_timers.get(flag).copied() {
+                    Some(0) | None => self.return_flag(flag, None),
+                    Some(timer) => { self.flag_return_timers.insert(flag, timer - 1); },
+                }
+            }
+        }
+    }
+
+    /**
+     * Check if a player is next to their team's base while their own flag is safe at home
+     */
+    fn at_own_base(&self, player: Entity, pos: &Vector3<f32>, radius: f32) -> bool {
+        self.flag_components.values().any(|flag| {
+            self.team_components.get(player) == Some(&flag.team)
+                && flag.state == FlagState::Home
+                && (Vector3::new(flag.home_x, flag.home_y, flag.home_z) - pos).norm() < radius
+        })
+    }
+
+    fn carrying(&self, player: Entity) -> bool {
+        self.flag_components.values().any(|flag| flag.carrier == Some(player))
+    }
+
+    /**
+     * Player whose lasso is tied to an entity, if any
+     */
+    fn lasso_owner(&self, entity: Entity) -> Option<Entity> {
+        self.players.iter().copied().find(|&player| {
+            self.player_lasso_phys_components.get(player).map_or(false, |lasso| lasso.anchor == entity)
+        })
+    }
+
+    fn take_flag(&mut self, flag: Entity, player: Entity) {
+        self.flag_components[flag].state = FlagState::Carried;
+        self.flag_components[flag].carrier = Some(player);
+        self.flag_return_timers.remove(flag);
+        // carried flags pass through their carrier
+        self.collider_set[self.physics_components[flag].collider_handle].set_sensor(true);
+        self.flag_event("flag_taken_event", EventType::FlagTakenEvent { flag, player });
+    }
+
+    fn drop_flag(&mut self, flag: Entity, player: Entity) {
+        self.flag_components[flag].state = FlagState::Dropped;
+        self.flag_components[flag].carrier = None;
+        self.flag_return_timers.insert(flag, self.config.ctf.return_ticks);
+        self.collider_set[self.physics_components[flag].collider_handle].set_sensor(false);
+        self.flag_event("flag_drop_event", EventType::FlagDropEvent { flag, player });
+    }
+
+    pub fn return_flag(&mut self, flag: Entity, player: Option<Entity>) {
+        self.reset_flag(flag);
+        self.flag_event("flag_return_event", EventType::FlagReturnEvent { flag, player });
+    }
+
+    fn capture_flag(&mut self, flag: Entity, player: Entity) {
+        self.reset_flag(flag);
+        self.flag_event("flag_capture_event", EventType::FlagCaptureEvent { flag, player });
+    }
+
+    /**
+     * Put a flag back on its base, cutting any ropes tied to it
+     */
+    fn reset_flag(&mut self, flag: Entity) {
+        self.release_lassos_on(flag);
+
+        let component = &mut self.flag_components[flag];
+        component.state = FlagState::Home;
+        component.carrier = None;
+        let home = Vector3::new(component.home_x, component.home_y, component.home_z);
+        self.flag_return_timers.remove(flag);
+        self.collider_set[self.physics_components[flag].collider_handle].set_sensor(false);
+        let body = &mut self.rigid_body_set[self.physics_components[flag].handle];
+        body.set_translation(home, true);
+        body.set_linvel(Vector3::zeros(), true);
+        body.set_angvel(Vector3::zeros(), true);
+    }
+
+    fn flag_event(&mut self, name: &str, event_type: EventType) {
+        let event_key = self.name_components.insert(name.to_string());
+        self.events.push(event_key);
+        self.event_components.insert(event_key, EventComponent { lifetime: EVENT_LIFETIME, event_type });
+    }
+}
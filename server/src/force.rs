@@ -0,0 +1,60 @@
+use nalgebra::{Isometry3, Vector3};
+use rapier3d::prelude::*;
+
+use shared::*;
+use shared::shared_components::ForceKind;
+use crate::ecs::ECS;
+
+/**
+ * Force sources from the world file: gravity wells, repulsors and wind that push on
+ * everything dynamic inside them, players included
+ */
+impl ECS {
+    /**
+     * Move anchored sources along with their props, then push every body inside a source
+     */
+    pub fn apply_forces(&mut self) {
+        let dt = TICK_SPEED as f32 / 1000.0;
+        for i in 0..self.force_sources.len() {
+            // a source stays where its prop was if the prop is destroyed
+            if let Some(anchor) = self.force_sources[i].anchor {
+                if let Some(physics) = self.physics_components.get(anchor) {
+                    let pos = *self.rigid_body_set[physics.handle].translation();
+                    let field = &mut self.force_sources[i].field;
+                    field.x = pos.x;
+                    field.y = pos.y;
+                    field.z = pos.z;
+                }
+            }
+
+            let field = self.force_sources[i].field.clone();
+            let center = Vector3::new(field.x, field.y, field.z);
+            let mut bodies: Vec<RigidBodyHandle> = vec![];
+            self.query_pipeline.intersections_with_shape(&self.rigid_body_set, &self.collider_set, &Isometry3::translation(field.x, field.y, field.z),
+                &Ball::new(field.radius), QueryFilter::only_dynamic(), |handle| {
+                    // bodies with several colliders, like players with their heads, are pushed once
+                    if let Some(body) = self.collider_set[handle].parent() {
+                        if !bodies.contains(&body) {
+                            bodies.push(body);
+                        }
+                    }
+                    true
+                });
+
+            for handle in bodies {
+                let body = &mut self.rigid_body_set[handle];
+                let offset = body.translation() - center;
+                let dist = offset.norm();
+                let falloff = (1.0 - dist / field.radius).max(0.0);
+                let accel = match field.kind {
+                    ForceKind::Well(strength) if dist > 0.0 => -offset / dist * strength * falloff,
+                    ForceKind::Repulsor(strength) if dist > 0.0 => offset / dist * strength * falloff,
+                    ForceKind::Wind(x, y, z) => Vector3::new(x, y, z),
+                    _ => Vector3::zeros(),
+                };
+                let impulse = accel * body.mass() * dt;
+                body.apply_impulse(impulse, true);
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use nalgebra::{UnitQuaternion, Vector3};
+use rand::{thread_rng, Rng};
+use rapier3d::prelude::*;
+use slotmap::DefaultKey;
+
+use shared::shared_components::*;
+use crate::ecs::{ECS, EVENT_LIFETIME};
+use crate::init_world::model_radius;
+
+type Entity = DefaultKey;
+
+/**
+ * Destructible props: shots wear down a prop's health until it breaks into short-lived fragments
+ */
+impl ECS {
+    /**
+     * Take health off a destructible prop, breaking it once none is left
+     *
+     * @param   prop: the prop that was hit, props without health are left alone
+     * @param   player: who hit it
+     */
+    pub fn damage_prop(&mut self, prop: Entity, player: Entity, damage: u8) {
+        let health = match self.prop_health.get_mut(prop) {
+            Some(health) => health,
+            None => return,
+        };
+        *health = health.saturating_sub(damage as u16);
+        if *health == 0 {
+            self.break_prop(prop, player);
+        }
+    }
+
+    /**
+     * Replace a prop with fragments flying apart from where it was, carrying its momentum
+     */
+    fn break_prop(&mut self, prop: Entity, player: Entity) {
+        let config = self.config.fragments.clone();
+        let body = &self.rigid_body_set[self.physics_components[prop].handle];
+        let (pos, linvel, angvel) = (*body.translation(), *body.linvel(), *body.angvel());
+        let radius = self.collider_set[self.physics_components[prop].collider_handle].shape().compute_local_bounding_sphere().radius();
+        self.remove_prop(prop);
+
+        let model_radius = *self.model_radii.entry(config.shape.clone()).or_insert_with(|| model_radius(&config.shape));
+        let fragment_radius = radius * config.size;
+        let scale = if model_radius > 0.0 { fragment_radius / model_radius } else { 1.0 };
+        let mut rng = thread_rng();
+        for _ in 0..config.count {
+            let dir = rng.gen::<UnitQuaternion<f32>>() * Vector3::z();
+            let offset = dir * radius * 0.5;
+            let rot = rng.gen::<UnitQuaternion<f32>>();
+            // pieces keep the prop's spin as well as its velocity
+            let vel = linvel + angvel.cross(&offset) + dir * config.speed;
+            let spin = rng.gen::<UnitQuaternion<f32>>() * Vector3::z() * rng.gen_range(0.0..1.0);
+            let fragment = self.spawn_prop("fragment".to_string(), config.model.clone(), pos.x + offset.x, pos.y + offset.y, pos.z + offset.z,
+                rot.i, rot.j, rot.k, rot.w, true, SharedShape::ball(fragment_radius), scale, 1.0, 0.3, false, vel, spin);
+            self.fragment_timers.insert(fragment, config.lifetime);
+        }
+
+        let event_key = self.name_components.insert("break_event".to_string());
+        self.events.push(event_key);
+        self.event_components.insert(event_key, EventComponent { lifetime: EVENT_LIFETIME, event_type: EventType::BreakEvent { player, x: pos.x, y: pos.y, z: pos.z, radius } });
+        self.particle_components.insert(event_key, ParticleComponent {
+            x: pos.x,
+            y: pos.y,
+            z: pos.z,
+            normal_x: 0.0,
+            normal_y: 1.0,
+            normal_z: 0.0,
+            vel_x: linvel.x,
+            vel_y: linvel.y,
+            vel_z: linvel.z,
+        });
+    }
+
+    /**
+     * Count down fragment lifetimes and clear away the ones that ran out
+     */
+    pub fn update_fragments(&mut self) {
+        let mut expired: Vec<Entity> = vec![];
+        for (fragment, timer) in &mut self.fragment_timers {
+            *timer = timer.saturating_sub(1);
+            if *timer == 0 {
+                expired.push(fragment);
+            }
+        }
+        for fragment in expired {
+            self.remove_prop(fragment);
+        }
+    }
+
+    /**
+     * Take a prop out of the world, letting go of any lasso tied to it
+     */
+    pub fn remove_prop(&mut self, prop: Entity) {
+        self.release_lassos_on(prop);
+
+        self.rigid_body_set.remove(self.physics_components[prop].handle, &mut self.island_manager, &mut self.collider_set,
+            &mut self.impulse_joint_set, &mut self.multibody_joint_set, true);
+        self.dynamics.retain(|&entity| entity != prop);
+        self.renderables.retain(|&entity| entity != prop);
+        self.name_components.remove(prop);
+        self.physics_components.remove(prop);
+        self.position_components.remove(prop);
+        self.velocity_components.remove(prop);
+        self.model_components.remove(prop);
+        self.thrown_prop_components.remove(prop);
+        self.prop_health.remove(prop);
+        self.fragment_timers.remove(prop);
+        self.kinematic_components.remove(prop);
+        self.supply_crates.remove(prop);
+        self.prop_origins.remove(prop);
+        self.flag_components.remove(prop);
+        self.flag_return_timers.remove(prop);
+    }
+
+    /**
+     * Let go of every lasso tied to an entity
+     */
+    pub fn release_lassos_on(&mut self, anchor: Entity) {
+        let roped: Vec<Entity> = self.players.iter().copied()
+            .filter(|&player| self.player_lasso_phys_components.get(player).map_or(false, |lasso| lasso.anchor == anchor))
+            .collect();
+        for player in roped {
+            self.release_lasso(player);
+        }
+    }
+
+    /**
+     * Let go of a player's attached lasso, if they have one
+     */
+    pub fn release_lasso(&mut self, player: Entity) {
+        let lasso_phys = match self.player_lasso_phys_components.remove(player) {
+            Some(lasso_phys) => lasso_phys,
+            None => return,
+        };
+        self.impulse_joint_set.remove(lasso_phys.joint_handle, true);
+        self.player_lasso_components.remove(player);
+        self.lasso_cut_timers.remove(player);
+        let event_key = self.name_components.insert("lasso_release_event".to_string());
+        self.events.push(event_key);
+        self.event_components.insert(event_key, EventComponent { lifetime: EVENT_LIFETIME, event_type: EventType::LassoReleaseEvent { player } });
+    }
+}
@@ -0,0 +1,673 @@
+use slotmap::{DefaultKey, SecondaryMap, SparseSecondaryMap};
+use std::cmp::Reverse;
+
+use shared::*;
+use shared::shared_components::*;
+use crate::ecs::ECS;
+use crate::server_config::*;
+use crate::stats::{write_stats, PlayerStats, RoundStatsFile};
+
+type Entity = DefaultKey;
+
+/**
+ * Win conditions and scoring for a round. Hooks are driven by the events the ECS
+ * systems emit each tick, so modes never need to be wired into the systems themselves.
+ */
+pub trait GameMode {
+    fn on_player_join(&mut self, _ecs: &mut ECS, _player: Entity) {}
+    fn on_hit(&mut self, _ecs: &mut ECS, _player: Entity, _target: Entity) {}
+    // killer is None for deaths without a shooter, e.g. disconnects
+    fn on_death(&mut self, ecs: &mut ECS, player: Entity, killer: Option<Entity>);
+    // player brought the enemy flag to their base
+    fn on_capture(&mut self, _ecs: &mut ECS, _player: Entity, _flag: Entity) {}
+    fn on_tick(&mut self, _ecs: &mut ECS) {}
+    fn is_finished(&self, ecs: &ECS) -> bool;
+    fn standings(&self, ecs: &ECS) -> Vec<Standing>;
+    // players credited with winning the round
+    fn winners(&self, ecs: &ECS) -> Vec<Entity> {
+        self.standings(ecs).first().map(|standing| vec![standing.player]).unwrap_or_default()
+    }
+}
+
+pub fn new_game_mode(config: &ServerConfig) -> Box<dyn GameMode> {
+    match config.game_mode {
+        GameModeKind::Elimination => Box::new(Elimination::new()),
+        GameModeKind::Deathmatch => Box::new(Deathmatch::new(config.frag_limit, config.respawn_ticks)),
+        GameModeKind::TeamElimination => Box::new(TeamElimination::new()),
+        GameModeKind::CaptureTheFlag => Box::new(CaptureTheFlag::new(config)),
+        GameModeKind::KingOfTheHill => Box::new(KingOfTheHill::new(config)),
+    }
+}
+
+// per-player stats the round keeps for its results
+struct RoundStats {
+    shots: u16,
+    // shots that landed on a player
+    hits: u16,
+    assists: u16,
+    alive_ticks: u32,
+    lasso_throws: u16,
+    lasso_attaches: u16,
+    // metres travelled while alive
+    distance: f32,
+    top_speed: f32,
+}
+
+impl RoundStats {
+    fn default() -> RoundStats {
+        RoundStats {
+            shots: 0,
+            hits: 0,
+            assists: 0,
+            alive_ticks: 0,
+            lasso_throws: 0,
+            lasso_attaches: 0,
+            distance: 0.0,
+            top_speed: 0.0,
+        }
+    }
+}
+
+/**
+ * Runs a game mode for the duration of one round
+ */
+pub struct Round {
+    mode: Box<dyn GameMode>,
+    handled_events: SparseSecondaryMap<Entity, ()>,
+    stats: SecondaryMap<Entity, RoundStats>,
+    ticks: u32,
+}
+
+impl Round {
+    pub fn new(mode: Box<dyn GameMode>) -> Round {
+        Round {
+            mode,
+            handled_events: SparseSecondaryMap::new(),
+            stats: SecondaryMap::new(),
+            ticks: 0,
+        }
+    }
+
+    /**
+     * Notify the game mode of every player taking part in the round,
+     * starting a new match if the last one is over
+     */
+    pub fn start(&mut self, ecs: &mut ECS) {
+        if ecs.match_over {
+            ecs.match_scores.clear();
+            ecs.round_number = 0;
+            ecs.match_over = false;
+        }
+        ecs.round_number += 1;
+
+        for player in ecs.players.clone() {
+            if !ecs.match_scores.contains_key(player) {
+                ecs.match_scores.insert(player, MatchScore::default());
+            }
+            self.stats.insert(player, RoundStats::default());
+            self.mode.on_player_join(ecs, player);
+        }
+        ecs.standings = self.mode.standings(ecs);
+    }
+
+    /**
+     * Dispatch new events to the game mode, update standings and count down to the end of the round
+     */
+    pub fn update(&mut self, ecs: &mut ECS) {
+        for event in ecs.events.clone() {
+            if self.handled_events.contains_key(event) {
+                continue;
+            }
+            self.handled_events.insert(event, ());
+
+            match ecs.event_components[event].event_type.clone() {
+                EventType::FireEvent { player } => {
+                    if let Some(stats) = self.stats.get_mut(player) {
+                        stats.shots += 1;
+                    }
+                },
+                EventType::LassoThrowEvent { player } => {
+                    if let Some(stats) = self.stats.get_mut(player) {
+                        stats.lasso_throws += 1;
+                    }
+                },
+                EventType::LassoAttachEvent { player, .. } => {
+                    if let Some(stats) = self.stats.get_mut(player) {
+                        stats.lasso_attaches += 1;
+                    }
+                },
+                EventType::HitEvent { player, target, .. } if ecs.players.contains(&target) => {
+                    if let Some(stats) = self.stats.get_mut(player) {
+                        stats.hits = stats.hits.saturating_add(1);
+                    }
+                    self.mode.on_hit(ecs, player, target);
+                },
+                EventType::PropHitEvent { player, target, .. } if ecs.players.contains(&target) => {
+                    self.mode.on_hit(ecs, player, target);
+                },
+                EventType::DeathEvent { player, killer, assists, .. } => {
+                    for assist in assists {
+                        if let Some(stats) = self.stats.get_mut(assist) {
+                            stats.assists += 1;
+                        }
+                    }
+                    // nobody gets credit for players killed by the zone
+                    let killer = if killer == player { None } else { Some(killer) };
+                    self.mode.on_death(ecs, player, killer);
+                },
+                EventType::DisconnectEvent { player, killer } => {
+                    self.mode.on_death(ecs, player, killer);
+                },
+                EventType::FlagCaptureEvent { flag, player } => {
+                    self.mode.on_capture(ecs, player, flag);
+                },
+                _ => ()
+            }
+        }
+        self.handled_events.retain(|event, _| ecs.events.contains(&event));
+
+        if !ecs.round_over {
+            self.ticks += 1;
+            let dt = TICK_SPEED as f32 / 1000.0;
+            for (player, stats) in &mut self.stats {
+                if ecs.players.contains(&player) && ecs.player_health_components[player].alive {
+                    stats.alive_ticks += 1;
+                    let speed = ecs.rigid_body_set[ecs.physics_components[player].handle].linvel().norm();
+                    stats.distance += speed * dt;
+                    stats.top_speed = stats.top_speed.max(speed);
+                }
+            }
+        }
+
+        self.mode.on_tick(ecs);
+        ecs.standings = self.mode.standings(ecs);
+
+        if self.mode.is_finished(ecs) {
+            ecs.round_over = true;
+            if ecs.eor_countdown == 0 {
+                ecs.game_ended = true;
+                self.finish(ecs);
+            } else {
+                ecs.eor_countdown -= 1;
+            }
+        }
+    }
+
+    /**
+     * Add the round's results to the match scores and decide whether the match is over
+     */
+    fn finish(&mut self, ecs: &mut ECS) {
+        for standing in &ecs.standings {
+            if let Some(score) = ecs.match_scores.get_mut(standing.player) {
+                score.kills += standing.kills;
+                score.hits = score.hits.saturating_add(self.stats.get(standing.player).map_or(0, |stats| stats.hits));
+            }
+        }
+        for winner in self.mode.winners(ecs) {
+            if let Some(score) = ecs.match_scores.get_mut(winner) {
+                score.wins += 1;
+            }
+        }
+
+        let rounds = ecs.config.rounds_per_match.max(1);
+        let most_wins = ecs.match_scores.values().map(|score| score.wins).max().unwrap_or(0);
+        ecs.match_over = most_wins > rounds / 2 || ecs.round_number >= rounds;
+    }
+
+    /**
+     * Final placement and stats of every player still in the game, best first
+     */
+    pub fn results(&self, ecs: &ECS) -> RoundResults {
+        let results = ecs.standings.iter().enumerate().map(|(i, standing)| {
+            let (shots, hits, assists, alive_ticks) = match self.stats.get(standing.player) {
+                Some(stats) => (stats.shots, stats.hits, stats.assists, stats.alive_ticks),
+                None => (0, 0, 0, 0),
+            };
+            RoundResult {
+                player: standing.player,
+                placement: i as u8 + 1,
+                kills: standing.kills,
+                deaths: standing.deaths,
+                hits,
+                shots,
+                assists,
+                accuracy: if shots > 0 { hits as f32 / shots as f32 } else { 0.0 },
+                survival_time: (alive_ticks as u64 * TICK_SPEED) as f32 / 1000.0,
+            }
+        }).collect();
+        RoundResults { results }
+    }
+
+    /**
+     * Stats of every player still in the game, best first
+     */
+    fn player_stats(&self, ecs: &ECS) -> Vec<PlayerStats> {
+        ecs.standings.iter().enumerate().map(|(i, standing)| {
+            let default = RoundStats::default();
+            let stats = self.stats.get(standing.player).unwrap_or(&default);
+            PlayerStats {
+                name: ecs.account_name(standing.player),
+                bot: ecs.bot_components.contains_key(standing.player),
+                team: ecs.team_components.get(standing.player).copied(),
+                placement: i as u8 + 1,
+                kills: standing.kills,
+                deaths: standing.deaths,
+                assists: stats.assists,
+                shots: stats.shots,
+                hits: stats.hits,
+                accuracy: if stats.shots > 0 { stats.hits as f32 / stats.shots as f32 } else { 0.0 },
+                lasso_throws: stats.lasso_throws,
+                lasso_attaches: stats.lasso_attaches,
+                distance: stats.distance,
+                time_alive: (stats.alive_ticks as u64 * TICK_SPEED) as f32 / 1000.0,
+                top_speed: stats.top_speed,
+            }
+        }).collect()
+    }
+
+    /**
+     * Write every player's stats for the round to a timestamped file, if enabled in the config
+     */
+    pub fn export_stats(&self, ecs: &ECS) {
+        if !ecs.config.stats.enabled {
+            return;
+        }
+        write_stats(&ecs.config.stats, &RoundStatsFile {
+            game_mode: format!("{:?}", ecs.config.game_mode),
+            round: ecs.round_number,
+            duration: (self.ticks as u64 * TICK_SPEED) as f32 / 1000.0,
+            players: self.player_stats(ecs),
+        });
+    }
+
+    /**
+     * Add the round to the match history, rating the match once it is over. Bots are left out
+     */
+    pub fn record_history(&self, ecs: &mut ECS) {
+        let stats: Vec<PlayerStats> = self.player_stats(ecs).into_iter().filter(|stats| !stats.bot).collect();
+        let mut order: Vec<Entity> = vec![];
+        if ecs.match_over {
+            order = ecs.standings.iter().map(|standing| standing.player).filter(|&player| !ecs.bot_components.contains_key(player)).collect();
+            // over several rounds, rank by match wins then total kills like the results screen
+            if ecs.config.rounds_per_match > 1 {
+                order.sort_by_key(|&player| {
+                    let score = ecs.match_scores.get(player).copied().unwrap_or(MatchScore::default());
+                    (Reverse(score.wins), Reverse(score.kills))
+                });
+            }
+        }
+        let match_order: Vec<(String, Option<u8>)> = order.iter()
+            .map(|&player| (ecs.account_name(player), ecs.team_components.get(player).copied()))
+            .collect();
+        ecs.history.record_round(&stats, &match_order);
+    }
+}
+
+/**
+ * Last player standing wins, dead players stay dead
+ */
+pub struct Elimination {
+    kills: SecondaryMap<Entity, u16>,
+    eliminated: Vec<Entity>,
+}
+
+impl Elimination {
+    pub fn new() -> Elimination {
+        Elimination {
+            kills: SecondaryMap::new(),
+            eliminated: vec![],
+        }
+    }
+}
+
+impl GameMode for Elimination {
+    fn on_player_join(&mut self, _ecs: &mut ECS, player: Entity) {
+        self.kills.insert(player, 0);
+    }
+
+    fn on_death(&mut self, _ecs: &mut ECS, player: Entity, killer: Option<Entity>) {
+        if let Some(killer) = killer {
+            if let Some(kills) = self.kills.get_mut(killer) {
+                *kills += 1;
+            }
+        }
+        if !self.eliminated.contains(&player) {
+            self.eliminated.push(player);
+        }
+    }
+
+    fn is_finished(&self, ecs: &ECS) -> bool {
+        ecs.active_players <= 1
+    }
+
+    fn standings(&self, ecs: &ECS) -> Vec<Standing> {
+        // survivors first, then players in reverse order of elimination
+        let survivors = ecs.players.iter().filter(|player| !self.eliminated.contains(player));
+        survivors.chain(self.eliminated.iter().rev()).filter(|&&player| ecs.players.contains(&player)).map(|&player| {
+            let kills = self.kills.get(player).copied().unwrap_or(0);
+            Standing {
+                player,
+                score: kills as i32,
+                kills,
+                deaths: self.eliminated.contains(&player) as u16,
+            }
+        }).collect()
+    }
+}
+
+/**
+ * Last team with a player standing wins
+ */
+pub struct TeamElimination {
+    elimination: Elimination,
+}
+
+impl TeamElimination {
+    pub fn new() -> TeamElimination {
+        TeamElimination {
+            elimination: Elimination::new(),
+        }
+    }
+
+    fn team_alive(ecs: &ECS, team: u8) -> bool {
+        ecs.players.iter().any(|&player| {
+            ecs.team_components.get(player) == Some(&team) && ecs.player_health_components[player].alive
+        })
+    }
+}
+
+impl GameMode for TeamElimination {
+    fn on_player_join(&mut self, ecs: &mut ECS, player: Entity) {
+        self.elimination.on_player_join(ecs, player);
+    }
+
+    fn on_death(&mut self, ecs: &mut ECS, player: Entity, killer: Option<Entity>) {
+        // team kills do not count towards the killer's score
+        let killer = killer.filter(|&killer| !ECS::same_team(&ecs.team_components, player, killer));
+        self.elimination.on_death(ecs, player, killer);
+    }
+
+    fn is_finished(&self, ecs: &ECS) -> bool {
+        let teams_alive = (0..ecs.config.team_count()).filter(|&team| TeamElimination::team_alive(ecs, team)).count();
+        teams_alive <= 1
+    }
+
+    fn standings(&self, ecs: &ECS) -> Vec<Standing> {
+        // members of teams still in play rank above everyone else, even if they died
+        let mut standings = self.elimination.standings(ecs);
+        standings.sort_by_key(|standing| {
+            match ecs.team_components.get(standing.player) {
+                Some(&team) => !TeamElimination::team_alive(ecs, team),
+                None => true,
+            }
+        });
+        standings
+    }
+
+    fn winners(&self, ecs: &ECS) -> Vec<Entity> {
+        // everyone on the surviving team wins, including teammates who died
+        let team = self.standings(ecs).first().and_then(|standing| ecs.team_components.get(standing.player).copied());
+        match team {
+            Some(team) => ecs.players.iter().copied().filter(|&player| ecs.team_components.get(player) == Some(&team)).collect(),
+            None => vec![],
+        }
+    }
+}
+
+/**
+ * Players respawn after dying, first to the frag limit wins
+ */
+pub struct Deathmatch {
+    frag_limit: u16,
+    respawn_ticks: u16,
+    kills: SecondaryMap<Entity, u16>,
+    deaths: SecondaryMap<Entity, u16>,
+    respawn_timers: SecondaryMap<Entity, u16>,
+}
+
+impl Deathmatch {
+    pub fn new(frag_limit: u16, respawn_ticks: u16) -> Deathmatch {
+        Deathmatch {
+            frag_limit,
+            respawn_ticks,
+            kills: SecondaryMap::new(),
+            deaths: SecondaryMap::new(),
+            respawn_timers: SecondaryMap::new(),
+        }
+    }
+}
+
+impl GameMode for Deathmatch {
+    fn on_player_join(&mut self, _ecs: &mut ECS, player: Entity) {
+        self.kills.insert(player, 0);
+        self.deaths.insert(player, 0);
+    }
+
+    fn on_death(&mut self, ecs: &mut ECS, player: Entity, killer: Option<Entity>) {
+        if let Some(killer) = killer {
+            if let Some(kills) = self.kills.get_mut(killer) {
+                *kills += 1;
+            }
+        }
+        if let Some(deaths) = self.deaths.get_mut(player) {
+            *deaths += 1;
+        }
+        // disconnected players never come back
+        if ecs.connected(player) {
+            self.respawn_timers.insert(player, self.respawn_ticks);
+        }
+    }
+
+    fn on_tick(&mut self, ecs: &mut ECS) {
+        let mut respawned = vec![];
+        for (player, timer) in &mut self.respawn_timers {
+            if *timer > 0 {
+                *timer -= 1;
+            } else {
+                respawned.push(player);
+            }
+        }
+        for player in respawned {
+            self.respawn_timers.remove(player);
+            if ecs.players.contains(&player) && ecs.connected(player) {
+                ecs.respawn_player(player);
+            }
+        }
+    }
+
+    fn is_finished(&self, ecs: &ECS) -> bool {
+        let connected = ecs.players.iter().filter(|&&player| ecs.connected(player)).count();
+        // bots don't keep a round going on their own
+        let humans = ecs.players.iter().filter(|&&player| ecs.connected(player) && !ecs.bot_components.contains_key(player)).count();
+        connected <= 1 || humans == 0 || self.kills.values().any(|&kills| kills >= self.frag_limit)
+    }
+
+    fn standings(&self, ecs: &ECS) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = ecs.players.iter().map(|&player| {
+            let kills = self.kills.get(player).copied().unwrap_or(0);
+            Standing {
+                player,
+                score: kills as i32,
+                kills,
+                deaths: self.deaths.get(player).copied().unwrap_or(0),
+            }
+        }).collect();
+        standings.sort_by(|a, b| b.score.cmp(&a.score).then(a.deaths.cmp(&b.deaths)));
+        standings
+    }
+}
+
+/**
+ * Teams score by bringing the enemy flag to their own base, players respawn after dying
+ * and the first team to the capture limit wins
+ */
+pub struct CaptureTheFlag {
+    // kills, deaths and respawns work like in deathmatch, just without a frag limit
+    deathmatch: Deathmatch,
+    capture_limit: u16,
+    captures: SecondaryMap<Entity, u16>,
+    team_captures: Vec<u16>,
+}
+
+impl CaptureTheFlag {
+    pub fn new(config: &ServerConfig) -> CaptureTheFlag {
+        CaptureTheFlag {
+            deathmatch: Deathmatch::new(u16::MAX, config.respawn_ticks),
+            capture_limit: config.ctf.capture_limit,
+            captures: SecondaryMap::new(),
+            team_captures: vec![0; config.team_count() as usize],
+        }
+    }
+
+    fn team_captures(&self, ecs: &ECS, player: Entity) -> u16 {
+        ecs.team_components.get(player).and_then(|&team| self.team_captures.get(team as usize)).copied().unwrap_or(0)
+    }
+}
+
+impl GameMode for CaptureTheFlag {
+    fn on_player_join(&mut self, ecs: &mut ECS, player: Entity) {
+        self.deathmatch.on_player_join(ecs, player);
+        self.captures.insert(player, 0);
+    }
+
+    fn on_death(&mut self, ecs: &mut ECS, player: Entity, killer: Option<Entity>) {
+        // team kills do not count towards the killer's score
+        let killer = killer.filter(|&killer| !ECS::same_team(&ecs.team_components, player, killer));
+        self.deathmatch.on_death(ecs, player, killer);
+    }
+
+    fn on_capture(&mut self, ecs: &mut ECS, player: Entity, _flag: Entity) {
+        if let Some(captures) = self.captures.get_mut(player) {
+            *captures += 1;
+        }
+        if let Some(&team) = ecs.team_components.get(player) {
+            if let Some(captures) = self.team_captures.get_mut(team as usize) {
+                *captures += 1;
+            }
+        }
+    }
+
+    fn on_tick(&mut self, ecs: &mut ECS) {
+        self.deathmatch.on_tick(ecs);
+    }
+
+    fn is_finished(&self, ecs: &ECS) -> bool {
+        self.team_captures.iter().any(|&captures| captures >= self.capture_limit) || self.deathmatch.is_finished(ecs)
+    }
+
+    fn standings(&self, ecs: &ECS) -> Vec<Standing> {
+        // members of the team with the most captures rank first, then by their own captures
+        let mut standings = self.deathmatch.standings(ecs);
+        for standing in &mut standings {
+            standing.score = self.captures.get(standing.player).copied().unwrap_or(0) as i32;
+        }
+        standings.sort_by(|a, b| {
+            self.team_captures(ecs, b.player).cmp(&self.team_captures(ecs, a.player))
+                .then(b.score.cmp(&a.score))
+                .then(b.kills.cmp(&a.kills))
+        });
+        standings
+    }
+
+    fn winners(&self, ecs: &ECS) -> Vec<Entity> {
+        // the whole team with the most captures wins
+        let team = self.standings(ecs).first().and_then(|standing| ecs.team_components.get(standing.player).copied());
+        match team {
+            Some(team) => ecs.players.iter().copied().filter(|&player| ecs.team_components.get(player) == Some(&team)).collect(),
+            None => vec![],
+        }
+    }
+}
+
+/**
+ * Players or teams score for every tick they hold the active hill, players respawn after dying
+ * and the first to the score limit wins
+ */
+pub struct KingOfTheHill {
+    deathmatch: Deathmatch,
+    // ticks of holding needed to win
+    limit_ticks: u32,
+    held_ticks: SecondaryMap<Entity, u32>,
+    team_held_ticks: Vec<u32>,
+}
+
+impl KingOfTheHill {
+    pub fn new(config: &ServerConfig) -> KingOfTheHill {
+        KingOfTheHill {
+            deathmatch: Deathmatch::new(u16::MAX, config.respawn_ticks),
+            limit_ticks: (config.koth.score_limit as u64 * 1000 / TICK_SPEED) as u32,
+            held_ticks: SecondaryMap::new(),
+            team_held_ticks: vec![0; config.team_count() as usize],
+        }
+    }
+
+    fn team_held_ticks(&self, ecs: &ECS, player: Entity) -> u32 {
+        ecs.team_components.get(player).and_then(|&team| self.team_held_ticks.get(team as usize)).copied().unwrap_or(0)
+    }
+}
+
+impl GameMode for KingOfTheHill {
+    fn on_player_join(&mut self, ecs: &mut ECS, player: Entity) {
+        self.deathmatch.on_player_join(ecs, player);
+        self.held_ticks.insert(player, 0);
+    }
+
+    fn on_death(&mut self, ecs: &mut ECS, player: Entity, killer: Option<Entity>) {
+        // team kills do not count towards the killer's score
+        let killer = killer.filter(|&killer| !ECS::same_team(&ecs.team_components, player, killer));
+        self.deathmatch.on_death(ecs, player, killer);
+    }
+
+    fn on_tick(&mut self, ecs: &mut ECS) {
+        self.deathmatch.on_tick(ecs);
+        if ecs.round_over {
+            return;
+        }
+        if let Some(holder) = ecs.hill_holder {
+            if let Some(held) = self.held_ticks.get_mut(holder) {
+                *held += 1;
+            }
+            if let Some(&team) = ecs.team_components.get(holder) {
+                if let Some(held) = self.team_held_ticks.get_mut(team as usize) {
+                    *held += 1;
+                }
+            }
+        }
+    }
+
+    fn is_finished(&self, ecs: &ECS) -> bool {
+        let limit_reached = if self.team_held_ticks.is_empty() {
+            self.held_ticks.values().any(|&held| held >= self.limit_ticks)
+        } else {
+            self.team_held_ticks.iter().any(|&held| held >= self.limit_ticks)
+        };
+        limit_reached || self.deathmatch.is_finished(ecs)
+    }
+
+    fn standings(&self, ecs: &ECS) -> Vec<Standing> {
+        // score is seconds spent holding the hill, teams with the most time rank first
+        let mut standings = self.deathmatch.standings(ecs);
+        for standing in &mut standings {
+            let held = self.held_ticks.get(standing.player).copied().unwrap_or(0);
+            standing.score = (held as u64 * TICK_SPEED / 1000) as i32;
+        }
+        standings.sort_by(|a, b| {
+            self.team_held_ticks(ecs, b.player).cmp(&self.team_held_ticks(ecs, a.player))
+                .then(b.score.cmp(&a.score))
+                .then(b.kills.cmp(&a.kills))
+        });
+        standings
+    }
+
+    fn winners(&self, ecs: &ECS) -> Vec<Entity> {
+        let leader = match self.standings(ecs).first() {
+            Some(standing) => standing.player,
+            None => return vec![],
+        };
+        // in team play the whole leading team wins
+        match ecs.team_components.get(leader).copied() {
+            Some(team) => ecs.players.iter().copied().filter(|&player| ecs.team_components.get(player) == Some(&team)).collect(),
+            None => vec![leader],
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use nalgebra::Isometry3;
+use rapier3d::prelude::*;
+use slotmap::{DefaultKey, KeyData};
+
+use crate::ecs::ECS;
+use crate::init_world::init_hills;
+use crate::server_config::GameModeKind;
+
+type Entity = DefaultKey;
+
+/**
+ * King of the hill: one hill at a time is in play, whoever stands in it alone captures it
+ * and then scores for as long as they hold it
+ */
+impl ECS {
+    /**
+     * Load the hills from the world file and put the first one in play, only in king of the hill
+     */
+    pub fn spawn_hills(&mut self) {
+        self.hills.clear();
+        self.hill_ticks = 0;
+        self.hill_holder = None;
+        if self.config.game_mode != GameModeKind::KingOfTheHill {
+            return;
+        }
+        init_hills(&mut self.hills);
+        if let Some(hill) = self.hills.first_mut() {
+            hill.active = true;
+        }
+    }
+
+    /**
+     * Rotate hills on schedule, then advance the capture of the active hill by whoever is in it
+     */
+    pub fn update_hills(&mut self) {
+        self.hill_holder = None;
+        if self.hills.is_empty() {
+            return;
+        }
+        let koth = self.config.koth.clone();
+
+        let mut active = self.hills.iter().position(|hill| hill.active).unwrap_or(0);
+        if self.hill_ticks >= koth.rotate_ticks {
+            self.hill_ticks = 0;
+            let hill = &mut self.hills[active];
+            hill.active = false;
+            hill.owner = None;
+            hill.progress = 0.0;
+            hill.contested = false;
+            active = (active + 1) % self.hills.len();
+        }
+        self.hill_ticks += 1;
+        self.hills[active].active = true;
+
+        let occupants = self.hill_occupants(active);
+        let contested = occupants.iter().any(|&player| !self.same_side(player, occupants[0]));
+        self.hills[active].contested = contested;
+        if occupants.is_empty() || contested {
+            return;
+        }
+
+        let occupant = occupants[0];
+        let rate = 1.0 / koth.capture_ticks.max(1) as f32;
+        let held = self.hills[active].owner.map(|owner| self.same_side(owner, occupant));
+        let hill = &mut self.hills[active];
+        match held {
+            Some(true) => {
+                hill.progress = (hill.progress + rate).min(1.0);
+                if hill.progress >= 1.0 {
+                    self.hill_holder = Some(occupant);
+                }
+            },
+            // someone else's hold has to be worn down before the hill can be taken
+            Some(false) => {
+                hill.progress -= rate;
+                if hill.progress <= 0.0 {
+                    hill.owner = None;
+                    hill.progress = 0.0;
+                }
+            },
+            None => {
+                hill.owner = Some(occupant);
+                hill.progress = rate;
+            },
+        }
+    }
+
+    /**
+     * Living players inside a hill, found with an intersection query against its sphere
+     */
+    fn hill_occupants(&self, index: usize) -> Vec<Entity> {
+        let hill = &self.hills[index];
+        let shape = Ball::new(hill.radius);
+        let pos = Isometry3::translation(hill.x, hill.y, hill.z);
+        let mut occupants: Vec<Entity> = vec![];
+        self.query_pipeline.intersections_with_shape(&self.rigid_body_set, &self.collider_set, &pos, &shape, QueryFilter::only_dynamic(), |handle| {
+            let entity = DefaultKey::from(KeyData::from_ffi(self.collider_set[handle].user_data as u64));
+            // players have a body and a head collider, count them once
+            if self.players.contains(&entity) && self.player_health_components[entity].alive && !occupants.contains(&entity) {
+                occupants.push(entity);
+            }
+            true
+        });
+        occupants
+    }
+
+    /**
+     * Check if two players share a hill, teammates do in team modes
+     */
+    fn same_side(&self, a: Entity, b: Entity) -> bool {
+        a == b || ECS::same_team(&self.team_components, a, b)
+    }
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use shared::shared_components::LeaderboardEntry;
+use crate::server_config::{HistoryConfig, ServerConfig};
+use crate::stats::PlayerStats;
+
+// a player's lifetime totals, kept under their name
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerRecord {
+    pub rounds: u32,
+    pub matches: u32,
+    pub wins: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    pub shots: u32,
+    pub hits: u32,
+    // seconds spent alive
+    pub time_alive: f32,
+    pub rating: f32,
+}
+
+impl PlayerRecord {
+    pub fn default(rating: f32) -> PlayerRecord {
+        PlayerRecord {
+            rounds: 0,
+            matches: 0,
+            wins: 0,
+            kills: 0,
+            deaths: 0,
+            assists: 0,
+            shots: 0,
+            hits: 0,
+            time_alive: 0.0,
+            rating,
+        }
+    }
+
+    pub fn win_rate(&self) -> f32 {
+        if self.matches > 0 { self.wins as f32 / self.matches as f32 } else { 0.0 }
+    }
+}
+
+/**
+ * Match history database: lifetime stats and an Elo rating per player name, stored as a JSON
+ * file next to the server and rewritten after every round
+ */
+pub struct History {
+    config: HistoryConfig,
+    pub players: HashMap<String, PlayerRecord>,
+}
+
+impl History {
+    /**
+     * Open the database, starting an empty one if the file doesn't exist yet or history is
+     * disabled. A file that can't be read is moved aside rather than overwritten by the next save
+     */
+    pub fn load(config: &HistoryConfig) -> History {
+        if !config.enabled {
+            return History { config: config.clone(), players: HashMap::new() };
+        }
+        let players = match fs::read_to_string(&config.path) {
+            Ok(j) => match serde_json::from_str(&j) {
+                Ok(players) => players,
+                Err(e) => {
+                    let backup = format!("{}.bad", config.path);
+                    eprintln!("Error deserializing match history \"{}\": {:?}, moving it to \"{}\" and starting over", config.path, e, backup);
+                    if let Err(e) = fs::rename(&config.path, &backup) {
+                        eprintln!("Error moving match history aside: {:?}", e);
+                    }
+                    HashMap::new()
+                },
+            },
+            Err(_) => HashMap::new(),
+        };
+        History { config: config.clone(), players }
+    }
+
+    fn save(&self) {
+        if !self.config.enabled {
+            return;
+        }
+        let j = serde_json::to_string_pretty(&self.players).expect("Match history serialization error");
+        // write next to the database first so a crash mid-write can't corrupt it
+        let temp = format!("{}.tmp", self.config.path);
+        match fs::write(&temp, j).and_then(|_| fs::rename(&temp, &self.config.path)) {
+            Ok(_) => (),
+            Err(e) => eprintln!("Error writing match history \"{}\": {:?}", self.config.path, e),
+        }
+    }
+
+    fn record(&mut self, name: &str) -> &mut PlayerRecord {
+        let rating = self.config.start_rating;
+        self.players.entry(name.to_string()).or_insert_with(|| PlayerRecord::default(rating))
+    }
+
+    /**
+     * Add a finished round to the lifetime totals, and rate the match if it was the last round
+     *
+     * @param   stats: every human player's stats for the round
+     * @param   match_order: names and teams best first when the match is over, otherwise empty
+     */
+    pub fn record_round(&mut self, stats: &[PlayerStats], match_order: &[(String, Option<u8>)]) {
+        if !self.config.enabled {
+            return;
+        }
+        for player in stats {
+            let record = self.record(&player.name);
+            record.rounds += 1;
+            record.kills += player.kills as u32;
+            record.deaths += player.deaths as u32;
+            record.assists += player.assists as u32;
+            record.shots += player.shots as u32;
+            record.hits += player.hits as u32;
+            record.time_alive += player.time_alive;
+        }
+        if !match_order.is_empty() {
+            self.rate_match(match_order);
+        }
+        self.save();
+    }
+
+    /**
+     * Multiplayer Elo: every pair of opponents is treated as a game won by whoever placed higher,
+     * with K split across the opponents so big lobbies don't swing ratings more than duels
+     */
+    fn rate_match(&mut self, order: &[(String, Option<u8>)]) {
+        let opposed = |a: usize, b: usize| order[a].1.is_none() || order[a].1 != order[b].1;
+        let ratings: Vec<f32> = order.iter().map(|(name, _)| self.record(name).rating).collect();
+        let mut deltas = vec![0.0; order.len()];
+        for a in 0..order.len() {
+            let opponents = (0..order.len()).filter(|&b| b != a && opposed(a, b)).count();
+            if opponents == 0 {
+                continue;
+            }
+            let k = self.config.k_factor / opponents as f32;
+            for b in (0..order.len()).filter(|&b| b != a && opposed(a, b)) {
+                let expected = 1.0 / (1.0 + 10.0_f32.powf((ratings[b] - ratings[a]) / 400.0));
+                let score = if a < b { 1.0 } else { 0.0 };
+                deltas[a] += k * (score - expected);
+            }
+        }
+
+        for (i, (name, team)) in order.iter().enumerate() {
+            // the winner's teammates share the win
+            let won = i == 0 || (team.is_some() && *team == order[0].1);
+            let record = self.record(name);
+            record.matches += 1;
+            record.wins += won as u32;
+            record.rating += deltas[i];
+        }
+    }
+
+    /**
+     * Lifetime stats of one player, if they have played before
+     */
+    pub fn entry(&self, name: &str) -> Option<LeaderboardEntry> {
+        self.players.get(name).map(|record| LeaderboardEntry {
+            name: name.to_string(),
+            rating: record.rating,
+            matches: record.matches,
+            win_rate: record.win_rate(),
+            kills: record.kills,
+            deaths: record.deaths,
+        })
+    }
+
+    /**
+     * Highest rated players, best first
+     *
+     * @param   count: how many players to list, 0 lists everyone
+     */
+    pub fn leaderboard(&self, count: usize) -> Vec<LeaderboardEntry> {
+        let mut names: Vec<&String> = self.players.keys().collect();
+        names.sort_by(|a, b| self.players[*b].rating.total_cmp(&self.players[*a].rating));
+        if count > 0 {
+            names.truncate(count);
+        }
+        names.into_iter().filter_map(|name| self.entry(name)).collect()
+    }
+}
+
+/**
+ * Answer an admin query about the match history from the command line, e.g.
+ * `history leaderboard [count]` or `history player <name>`
+ *
+ * @param   args: command line arguments after `history`
+ */
+pub fn admin_command(config: &ServerConfig, args: &[String]) {
+    if !config.history.enabled {
+        eprintln!("Match history is disabled in the config");
+        return;
+    }
+    let history = History::load(&config.history);
+    match args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>().as_slice() {
+        ["leaderboard"] | ["leaderboard", _] => {
+            let count = args.get(1).and_then(|count| count.parse().ok()).unwrap_or(0);
+            println!("{:<4} {:<20} {:>7} {:>7} {:>6} {:>6} {:>6}", "#", "name", "rating", "matches", "win%", "kills", "deaths");
+            for (i, entry) in history.leaderboard(count).iter().enumerate() {
+                println!("{:<4} {:<20} {:>7.0} {:>7} {:>6.1} {:>6} {:>6}", i + 1, entry.name, entry.rating, entry.matches, entry.win_rate * 100.0, entry.kills, entry.deaths);
+            }
+        },
+        ["player", name] => match history.players.get(*name) {
+            Some(record) => println!("{}", serde_json::to_string_pretty(record).unwrap()),
+            None => eprintln!("No match history for \"{name}\""),
+        },
+        _ => eprintln!("Unknown command, expected `history leaderboard [count]` or `history player <name>`"),
+    }
+}
@@ -1,5 +1,8 @@
 use crate::ecs::*;
+use crate::server_components::{ForceSource, KinematicComponent, PlayerSpawn, PropPath, ScheduledEvent, WorldEventSpec};
+use shared::shared_components::{ForceKind, ForceSourceComponent, HillComponent};
 use rapier3d::geometry::SharedShape;
+use rapier3d::dynamics::RigidBodyType;
 use nalgebra::{Isometry3, Translation3, UnitQuaternion, point, Point3, Unit, vector};
 use serde::Deserialize;
 use std::{fs};
@@ -57,6 +60,12 @@ struct Prop {
     max_linvel: f32,
     #[serde(default = "prop_default_max_angvel")]
     max_angvel: f32,
+    // props with health break into fragments once shot enough, None means indestructible
+    #[serde(default = "prop_default_health")]
+    health: Option<u16>,
+    // props with a path are kinematic, moved by script and unaffected by anything that hits them
+    #[serde(default = "prop_default_path")]
+    path: Option<PropPath>,
 }
 
 fn prop_default_name() -> String { "UNNAMED".to_string() }
@@ -71,15 +80,20 @@ fn prop_default_restitution() -> f32 { 0.3 }
 fn prop_default_border() -> bool { false }
 fn prop_default_max_linvel() -> f32 { 1.0 }
 fn prop_default_max_angvel() -> f32 { 0.1 }
+fn prop_default_health() -> Option<u16> { None }
+fn prop_default_path() -> Option<PropPath> { None }
 
 #[derive(Deserialize)]
 struct SpawnPoint {
     pos: (f32, f32, f32),
     #[serde(default = "spawnpoint_default_rot")]
     rot: Option<EulerRot>,
+    #[serde(default = "spawnpoint_default_team")]
+    team: Option<u8>,
 }
 
 fn spawnpoint_default_rot() -> Option<EulerRot> { None }
+fn spawnpoint_default_team() -> Option<u8> { None }
 
 fn load_scaled_model(path: &String, scale: f32) -> (Vec<Point3<f32>>, Vec<[u32; 3]>) {
     let path = Path::new(path);
@@ -142,7 +156,7 @@ pub fn init_world(ecs: &mut ECS) {
         } else {
             vector![0.0,0.0,0.0]
         };
-        ecs.spawn_prop(
+        let entity = ecs.spawn_prop(
             prop.name,
             prop.modelname,
             prop.pos.0,
@@ -161,10 +175,35 @@ pub fn init_world(ecs: &mut ECS) {
             linvel,
             angvel
         );
+        if let Some(health) = prop.health {
+            ecs.prop_health.insert(entity, health);
+        }
+        if let Some(path) = prop.path {
+            let body = &mut ecs.rigid_body_set[ecs.physics_components[entity].handle];
+            body.set_body_type(RigidBodyType::KinematicPositionBased, true);
+            body.set_linvel(vector![0.0, 0.0, 0.0], true);
+            body.set_angvel(vector![0.0, 0.0, 0.0], true);
+            let start = *body.position();
+            ecs.kinematic_components.insert(entity, KinematicComponent { path, start, ticks: 0 });
+            // moving props are replicated every tick even when the world file calls them static
+            if !prop.dynamic {
+                ecs.dynamics.push(entity);
+            }
+        } else if prop.dynamic {
+            ecs.prop_origins.insert(entity, *ecs.rigid_body_set[ecs.physics_components[entity].handle].position());
+        }
     }
 }
 
-pub fn init_player_spawns(spawnpoints: &mut Vec<Isometry3<f32>>) {
+/**
+ * Distance from a model's origin to its furthest vertex at scale 1
+ */
+pub fn model_radius(path: &String) -> f32 {
+    let (vertices, _) = load_scaled_model(path, 1.0);
+    vertices.iter().map(|vertex| vertex.coords.norm()).fold(0.0, f32::max)
+}
+
+pub fn init_player_spawns(spawnpoints: &mut Vec<PlayerSpawn>) {
     spawnpoints.clear();
     let j = fs::read_to_string("world/playerspawns.json").expect("Error reading file world/playerspawns.json");
     let spawns: Vec<SpawnPoint> = serde_json::from_str(&j).expect("Error deserializing world/playerspawns.json");
@@ -175,7 +214,113 @@ pub fn init_player_spawns(spawnpoints: &mut Vec<Isometry3<f32>>) {
                 thread_rng().gen()
             },
         };
-        spawnpoints.push(Isometry3::from_parts(Translation3::new(spawn.pos.0, spawn.pos.1, spawn.pos.2), rot));
+        spawnpoints.push(PlayerSpawn {
+            pos: Isometry3::from_parts(Translation3::new(spawn.pos.0, spawn.pos.1, spawn.pos.2), rot),
+            team: spawn.team,
+        });
+    }
+}
+
+#[derive(Deserialize)]
+struct Force {
+    kind: ForceKind,
+    #[serde(default = "force_default_pos")]
+    pos: (f32, f32, f32),
+    radius: f32,
+    // name of a prop the source is centered on and moves with, instead of pos
+    #[serde(default = "force_default_prop")]
+    prop: Option<String>,
+}
+
+fn force_default_pos() -> (f32, f32, f32) { (0.0, 0.0, 0.0) }
+fn force_default_prop() -> Option<String> { None }
+
+pub fn init_forces(ecs: &mut ECS) {
+    ecs.force_sources.clear();
+    let j = fs::read_to_string("world/forces.json").expect("Error reading file world/forces.json");
+    let forces: Vec<Force> = serde_json::from_str(&j).expect("Error deserializing world/forces.json");
+    for force in forces {
+        let anchor = match force.prop {
+            Some(name) => match ecs.name_components.iter().find(|(entity, prop)| **prop == name && ecs.physics_components.contains_key(*entity)) {
+                Some((entity, _)) => Some(entity),
+                None => {
+                    eprintln!("No prop named \"{name}\" to center a force source on, skipping it");
+                    continue;
+                },
+            },
+            None => None,
+        };
+        ecs.force_sources.push(ForceSource {
+            field: ForceSourceComponent {
+                x: force.pos.0,
+                y: force.pos.1,
+                z: force.pos.2,
+                radius: force.radius,
+                kind: force.kind,
+            },
+            anchor,
+        });
+    }
+}
+
+#[derive(Deserialize)]
+struct WorldEvent {
+    event: WorldEventSpec,
+    #[serde(default = "world_event_default_pos")]
+    pos: (f32, f32, f32),
+    #[serde(default = "world_event_default_spread")]
+    spread: f32,
+    // round tick the event first comes up on
+    start_ticks: u32,
+    #[serde(default = "world_event_default_interval_ticks")]
+    interval_ticks: Option<u32>,
+    #[serde(default = "world_event_default_chance")]
+    chance: f32,
+}
+
+fn world_event_default_pos() -> (f32, f32, f32) { (0.0, 0.0, 0.0) }
+fn world_event_default_spread() -> f32 { 100.0 }
+fn world_event_default_interval_ticks() -> Option<u32> { None }
+fn world_event_default_chance() -> f32 { 1.0 }
+
+pub fn init_world_events(events: &mut Vec<ScheduledEvent>) {
+    events.clear();
+    let j = fs::read_to_string("world/events.json").expect("Error reading file world/events.json");
+    let loadevents: Vec<WorldEvent> = serde_json::from_str(&j).expect("Error deserializing world/events.json");
+    for event in loadevents {
+        events.push(ScheduledEvent {
+            event: event.event,
+            center: vector![event.pos.0, event.pos.1, event.pos.2],
+            spread: event.spread,
+            next_tick: Some(event.start_ticks),
+            // an interval of 0 would fire every tick, treat it as a one-off
+            interval: event.interval_ticks.filter(|&interval| interval > 0),
+            chance: event.chance,
+        });
+    }
+}
+
+#[derive(Deserialize)]
+struct Hill {
+    pos: (f32, f32, f32),
+    radius: f32,
+}
+
+pub fn init_hills(hills: &mut Vec<HillComponent>) {
+    hills.clear();
+    let j = fs::read_to_string("world/hills.json").expect("Error reading file world/hills.json");
+    let loadhills: Vec<Hill> = serde_json::from_str(&j).expect("Error deserializing world/hills.json");
+    for hill in loadhills {
+        hills.push(HillComponent {
+            x: hill.pos.0,
+            y: hill.pos.1,
+            z: hill.pos.2,
+            radius: hill.radius,
+            active: false,
+            owner: None,
+            progress: 0.0,
+            contested: false,
+        });
     }
 }
 
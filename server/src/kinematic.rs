@@ -0,0 +1,66 @@
+use nalgebra::{Isometry3, Translation3, Unit, UnitQuaternion, Vector3};
+use std::f32::consts::PI;
+
+use shared::*;
+use crate::ecs::ECS;
+use crate::server_components::*;
+
+/**
+ * Kinematic props follow their scripted paths. Rapier derives their velocity from each step,
+ * so players riding or lassoed to them are carried along
+ */
+impl ECS {
+    /**
+     * Set where every kinematic prop should be at the end of this tick's physics step
+     */
+    pub fn update_kinematic_props(&mut self) {
+        for (prop, kinematic) in &mut self.kinematic_components {
+            kinematic.ticks += 1;
+            let t = (kinematic.ticks as u64 * TICK_SPEED) as f32 / 1000.0;
+            let next = path_position(&kinematic.path, &kinematic.start, t);
+            if let Some(physics) = self.physics_components.get(prop) {
+                self.rigid_body_set[physics.handle].set_next_kinematic_position(next);
+            }
+        }
+    }
+}
+
+/**
+ * Where a path puts a prop t seconds after it started out at start
+ */
+fn path_position(path: &PropPath, start: &Isometry3<f32>, t: f32) -> Isometry3<f32> {
+    match path {
+        PropPath::Orbit { center, axis, period } => {
+            let turn = rotation(axis, t, *period);
+            let center = Vector3::new(center.0, center.1, center.2);
+            let pos = center + turn * (start.translation.vector - center);
+            Isometry3::from_parts(Translation3::from(pos), turn * start.rotation)
+        },
+        PropPath::Waypoints { points, leg_time, easing } => {
+            if points.is_empty() || *leg_time <= 0.0 {
+                return *start;
+            }
+            let legs = t / leg_time;
+            let from = legs.floor() as usize % points.len();
+            let to = (from + 1) % points.len();
+            let s = legs.fract();
+            let s = if *easing == Easing::Smooth { s * s * (3.0 - 2.0 * s) } else { s };
+            let (a, b) = (Vector3::new(points[from].0, points[from].1, points[from].2), Vector3::new(points[to].0, points[to].1, points[to].2));
+            Isometry3::from_parts(Translation3::from(a.lerp(&b, s)), start.rotation)
+        },
+        PropPath::Spin { axis, period } => {
+            Isometry3::from_parts(start.translation, rotation(axis, t, *period) * start.rotation)
+        },
+    }
+}
+
+/**
+ * Rotation about axis after t seconds of turning once every period seconds
+ */
+fn rotation(axis: &(f32, f32, f32), t: f32, period: f32) -> UnitQuaternion<f32> {
+    let axis = Vector3::new(axis.0, axis.1, axis.2);
+    if period == 0.0 || axis.norm() == 0.0 {
+        return UnitQuaternion::identity();
+    }
+    UnitQuaternion::from_axis_angle(&Unit::new_normalize(axis), 2.0 * PI * t / period)
+}
@@ -1,5 +1,6 @@
 // use nalgebra::*;
 use rapier3d::prelude::*;
+use rapier3d::crossbeam;
 use std::collections::HashMap;
 use std::{time::Duration, time::Instant};
 use std::net::{TcpListener};
@@ -8,7 +9,19 @@ use polling::{Event, Poller};
 mod ecs;
 mod init_world;
 mod server_components;
+mod server_config;
+mod game_mode;
 mod common;
+mod bot;
+mod flag;
+mod hill;
+mod stats;
+mod history;
+mod fragment;
+mod force;
+mod kinematic;
+mod world_event;
+mod bounds;
 
 use shared::*;
 use shared::shared_functions::read_address_json;
@@ -18,13 +31,29 @@ fn main() {
     let gravity = vector![0.0, 0.0, 0.0];
     let integration_parameters = IntegrationParameters { dt: (TICK_SPEED as f32) / 1000.0, ..Default::default()};
     let physics_hooks = ();
-    let event_handler = ();
+    // contact force events drive impact damage, collision events are unused
+    let (collision_send, _collision_recv) = crossbeam::channel::unbounded();
+    let (contact_force_send, contact_force_recv) = crossbeam::channel::unbounded();
+    let event_handler = ChannelEventCollector::new(collision_send, contact_force_send);
+
+    let config = server_config::load_server_config("config.json");
+
+    // `history ...` runs an admin query against the match history instead of the server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(|arg| arg.as_str()) == Some("history") {
+        history::admin_command(&config, &args[1..]);
+        return;
+    }
 
-    let mut ecs = ecs::ECS::new();
+    let mut ecs = ecs::ECS::new(config);
 
     ecs.decomps = HashMap::new();
     init_world::init_world(&mut ecs);
+    init_world::init_forces(&mut ecs);
     init_world::init_player_spawns(&mut ecs.spawnpoints);
+    ecs.spawn_flags();
+    ecs.spawn_hills();
+    ecs.schedule_world_events();
     ecs.skies = (0..init_world::init_num_skies()).collect();
     ecs.sky = get_rand_from_vec(&mut ecs.skies);
 
@@ -53,6 +82,8 @@ fn main() {
                 ecs.connect_client(&listener);
                 poller.modify(&listener, Event::readable(key)).unwrap();
             }
+            // top the lobby up with bots, they are always ready
+            ecs.fill_bots();
             // check each connection for ready updates
             ecs.check_ready_updates();
             // if min. # of players reached and all players are ready
@@ -63,7 +94,10 @@ fn main() {
             }
         }
         poller.delete(&listener).unwrap();
+        ecs.balance_teams();
         ecs.update_player_models();
+        let mut round = game_mode::Round::new(game_mode::new_game_mode(&ecs.config));
+        round.start(&mut ecs);
         // GAME LOOP
         println!("[SERVER]: Starting game");
         while !ecs.game_ended {
@@ -71,10 +105,16 @@ fn main() {
             let start = Instant::now();
 
             ecs.receive_inputs();
+            ecs.update_bots();
 
             ecs.player_fire();
             ecs.player_lasso();
             ecs.player_move();
+            ecs.player_dash();
+            ecs.player_brake();
+            ecs.update_zone();
+            ecs.apply_forces();
+            ecs.update_kinematic_props();
 
             ecs.update_positions();
 
@@ -94,6 +134,15 @@ fn main() {
                 &event_handler,
             );
             ecs.query_pipeline.update(&ecs.rigid_body_set, &ecs.collider_set);
+            ecs.update_thrown_props();
+            ecs.handle_impacts(&contact_force_recv);
+            ecs.update_fragments();
+            ecs.update_world_events();
+            ecs.check_bounds();
+            ecs.update_flags();
+            ecs.update_hills();
+
+            round.update(&mut ecs);
 
             ecs.update_clients();
 
@@ -112,6 +161,9 @@ fn main() {
             }
         }
         println!("[SERVER]: Game over.");
+        ecs.send_round_results(&round.results(&ecs));
+        round.export_stats(&ecs);
+        round.record_history(&mut ecs);
 
         // reset the game
         ecs.reset();
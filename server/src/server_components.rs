@@ -1,7 +1,9 @@
-use nalgebra::{Vector3,UnitQuaternion,OPoint,Const};
+use nalgebra::{Vector3,UnitQuaternion,OPoint,Const,Isometry3};
 use rapier3d::prelude::*;
+use serde::Deserialize;
 use slotmap::DefaultKey;
 use std::net::{TcpStream};
+use shared::shared_components::{DamageSource, ForceSourceComponent, NebulaComponent, WorldEventKind};
 
 pub struct PhysicsComponent {
     pub handle: RigidBodyHandle,
@@ -23,6 +25,38 @@ impl PlayerCameraComponent {
             camera_right: vector![0.0, 0.0, 0.0]
         }
     }
+
+    /**
+     * Turn the camera to a new rotation and update its direction vectors
+     */
+    pub fn look(&mut self, rot: UnitQuaternion<f32>) {
+        self.rot = rot;
+        self.camera_front = rot * vector![0.0,0.0,-1.0];
+        self.camera_right = rot * vector![1.0,0.0,0.0];
+        self.camera_up = rot * vector![0.0,1.0,0.0];
+    }
+}
+
+// server-side brain of a bot player, bots have no network component
+pub struct BotComponent {
+    // opponent being fought, and ticks until the bot reacts to them
+    pub target: Option<DefaultKey>,
+    pub reaction: u16,
+    // prop the bot is travelling to
+    pub waypoint: Option<DefaultKey>,
+    // offset added to the bot's aim, resampled after every shot
+    pub aim_error: Vector3<f32>
+}
+
+impl BotComponent {
+    pub fn default() -> BotComponent {
+        BotComponent {
+            target: None,
+            reaction: 0,
+            waypoint: None,
+            aim_error: Vector3::zeros()
+        }
+    }
 }
 
 pub struct NetworkComponent {
@@ -40,4 +74,137 @@ pub struct PlayerLassoPhysComponent {
 
 pub struct PlayerLassoThrownComponent {
     pub entity: DefaultKey
-}
\ No newline at end of file
+}
+
+pub struct ThrownPropComponent {
+    pub thrower: DefaultKey,
+    // ticks left before the prop stops counting as a weapon
+    pub ticks: u16,
+    // velocity before the latest physics step, contacts have already slowed the prop down
+    pub prev_vel: Vector3<f32>
+}
+
+// the player who last shot or lassoed someone, credited if they die from a crash
+pub struct LastAttackerComponent {
+    pub attacker: DefaultKey,
+    pub ticks: u16
+}
+
+// one hit a player took, kept around for assists until it is assist_ticks old
+pub struct DamageRecord {
+    pub attacker: DefaultKey,
+    pub source: DamageSource,
+    pub damage: u8,
+    pub ticks: u16
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    // slow out of one waypoint and into the next
+    Smooth,
+}
+
+/**
+ * Scripted motion of a kinematic prop, times are in seconds
+ */
+#[derive(Deserialize, Clone)]
+pub enum PropPath {
+    // circle around center, turning to keep the same side facing it
+    Orbit {
+        center: (f32, f32, f32),
+        #[serde(default = "path_default_axis")]
+        axis: (f32, f32, f32),
+        period: f32,
+    },
+    // travel from point to point, looping back to the first
+    Waypoints {
+        points: Vec<(f32, f32, f32)>,
+        leg_time: f32,
+        #[serde(default = "path_default_easing")]
+        easing: Easing,
+    },
+    // turn in place
+    Spin {
+        #[serde(default = "path_default_axis")]
+        axis: (f32, f32, f32),
+        period: f32,
+    },
+}
+
+fn path_default_axis() -> (f32, f32, f32) { (0.0, 1.0, 0.0) }
+fn path_default_easing() -> Easing { Easing::Smooth }
+
+// a prop moved along a path instead of by physics, start is where it was placed
+pub struct KinematicComponent {
+    pub path: PropPath,
+    pub start: Isometry3<f32>,
+    pub ticks: u32
+}
+
+// a force source from the world file, following its anchor prop around if it has one
+pub struct ForceSource {
+    pub field: ForceSourceComponent,
+    pub anchor: Option<DefaultKey>
+}
+
+/**
+ * What a scheduled world event does when it fires, times are in ticks
+ */
+#[derive(Deserialize, Clone)]
+pub enum WorldEventSpec {
+    // asteroids launched from distance away towards the event's area, gone after lifetime
+    MeteorShower {
+        count: u8,
+        speed: f32,
+        size: f32,
+        distance: f32,
+        lifetime: u16,
+    },
+    // crates scattered over the event's area, they stay until opened or the round ends
+    SupplyDrop {
+        count: u8,
+        size: f32,
+    },
+    // a cloud somewhere in the event's area that clears after duration
+    Nebula {
+        radius: f32,
+        duration: u32,
+    },
+}
+
+impl WorldEventSpec {
+    pub fn kind(&self) -> WorldEventKind {
+        match self {
+            WorldEventSpec::MeteorShower { .. } => WorldEventKind::MeteorShower,
+            WorldEventSpec::SupplyDrop { .. } => WorldEventKind::SupplyDrop,
+            WorldEventSpec::Nebula { .. } => WorldEventKind::Nebula,
+        }
+    }
+}
+
+// a world event from the world file waiting for its turn
+pub struct ScheduledEvent {
+    pub event: WorldEventSpec,
+    // the event happens somewhere within spread of center
+    pub center: Vector3<f32>,
+    pub spread: f32,
+    // round tick the event next comes up on, None once it is done for the round
+    pub next_tick: Option<u32>,
+    // ticks between repeats, None for a one-off
+    pub interval: Option<u32>,
+    // chance the event actually happens each time it comes up
+    pub chance: f32
+}
+
+// a nebula in the world and the ticks until it clears
+pub struct Nebula {
+    pub cloud: NebulaComponent,
+    pub ticks: u32
+}
+
+pub struct PlayerSpawn {
+    pub pos: Isometry3<f32>,
+    // spawn is reserved for this team in team modes, None means anyone can use it
+    pub team: Option<u8>
+}
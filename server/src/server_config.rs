@@ -0,0 +1,719 @@
+use serde::Deserialize;
+use std::fs;
+use shared::shared_components::HitRegion;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum GameModeKind {
+    Elimination,
+    Deathmatch,
+    TeamElimination,
+    CaptureTheFlag,
+    KingOfTheHill,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ServerConfig {
+    #[serde(default = "config_default_game_mode")]
+    pub game_mode: GameModeKind,
+    // deathmatch: kills needed to win the round
+    #[serde(default = "config_default_frag_limit")]
+    pub frag_limit: u16,
+    // deathmatch: ticks a dead player waits before respawning
+    #[serde(default = "config_default_respawn_ticks")]
+    pub respawn_ticks: u16,
+    // team modes: number of teams players are split into
+    #[serde(default = "config_default_num_teams")]
+    pub num_teams: u8,
+    #[serde(default = "config_default_friendly_fire")]
+    pub friendly_fire: bool,
+    // even out team sizes when the game starts
+    #[serde(default = "config_default_auto_balance")]
+    pub auto_balance: bool,
+    // rounds in a match, first to win a majority of them takes the match
+    #[serde(default = "config_default_rounds_per_match")]
+    pub rounds_per_match: u8,
+    // ticks damage counts towards an assist if the player dies
+    #[serde(default = "config_default_assist_ticks")]
+    pub assist_ticks: u16,
+    #[serde(default = "config_default_zone")]
+    pub zone: ZoneConfig,
+    #[serde(default = "config_default_lasso")]
+    pub lasso: LassoConfig,
+    #[serde(default = "config_default_impact")]
+    pub impact: ImpactConfig,
+    #[serde(default = "config_default_fuel")]
+    pub fuel: FuelConfig,
+    #[serde(default = "config_default_dash")]
+    pub dash: DashConfig,
+    #[serde(default = "config_default_brake")]
+    pub brake: BrakeConfig,
+    #[serde(default = "config_default_weapon")]
+    pub weapon: WeaponConfig,
+    #[serde(default = "config_default_spawn")]
+    pub spawn: SpawnConfig,
+    #[serde(default = "config_default_bots")]
+    pub bots: BotConfig,
+    #[serde(default = "config_default_ctf")]
+    pub ctf: CtfConfig,
+    #[serde(default = "config_default_koth")]
+    pub koth: KothConfig,
+    #[serde(default = "config_default_stats")]
+    pub stats: StatsConfig,
+    #[serde(default = "config_default_history")]
+    pub history: HistoryConfig,
+    #[serde(default = "config_default_fragments")]
+    pub fragments: FragmentConfig,
+    #[serde(default = "config_default_bounds")]
+    pub bounds: BoundsConfig,
+}
+
+/**
+ * One step of the zone schedule: wait, then shrink to the given radius
+ */
+#[derive(Deserialize, Clone)]
+pub struct ZoneStage {
+    pub delay_ticks: u32,
+    pub shrink_ticks: u32,
+    pub radius: f32,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ZoneConfig {
+    // off unless turned on, it is meant for elimination modes where nobody respawns
+    #[serde(default = "zone_default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "zone_default_center")]
+    pub center: [f32; 3],
+    #[serde(default = "zone_default_start_radius")]
+    pub start_radius: f32,
+    #[serde(default = "zone_default_stages")]
+    pub stages: Vec<ZoneStage>,
+    // ticks between each point of damage dealt to players outside the zone
+    #[serde(default = "zone_default_damage_ticks")]
+    pub damage_ticks: u16,
+    // impulse applied every tick to push players outside back towards the center
+    #[serde(default = "zone_default_push")]
+    pub push: f32,
+}
+
+impl ZoneConfig {
+    /**
+     * Radius of the zone after the given number of ticks into the round
+     */
+    pub fn radius_at(&self, ticks: u32) -> f32 {
+        let mut radius = self.start_radius;
+        let mut t = ticks;
+        for stage in &self.stages {
+            if t < stage.delay_ticks {
+                return radius;
+            }
+            t -= stage.delay_ticks;
+            if t < stage.shrink_ticks {
+                return radius + (stage.radius - radius) * (t as f32 / stage.shrink_ticks as f32);
+            }
+            t -= stage.shrink_ticks;
+            radius = stage.radius;
+        }
+        radius
+    }
+}
+
+impl ServerConfig {
+    /**
+     * Number of teams in play, 0 if the game mode is free-for-all
+     */
+    pub fn team_count(&self) -> u8 {
+        match self.game_mode {
+            GameModeKind::TeamElimination | GameModeKind::CaptureTheFlag => self.num_teams.max(2),
+            GameModeKind::KingOfTheHill if self.koth.teams => self.num_teams.max(2),
+            _ => 0,
+        }
+    }
+}
+
+/**
+ * Rope tuning, lengths are in world units and speeds are per tick
+ */
+#[derive(Deserialize, Clone)]
+pub struct LassoConfig {
+    #[serde(default = "lasso_default_fire_vel")]
+    pub fire_vel: f32,
+    // furthest a thrown lasso travels before it is released
+    #[serde(default = "lasso_default_max_dist")]
+    pub max_dist: f32,
+    #[serde(default = "lasso_default_slack")]
+    pub slack: f32,
+    #[serde(default = "lasso_default_min_length")]
+    pub min_length: f32,
+    #[serde(default = "lasso_default_max_length")]
+    pub max_length: f32,
+    #[serde(default = "lasso_default_reel_in_speed")]
+    pub reel_in_speed: f32,
+    #[serde(default = "lasso_default_reel_out_speed")]
+    pub reel_out_speed: f32,
+    // impulse pulling the player and the anchor together while reeling in
+    #[serde(default = "lasso_default_pull_impulse")]
+    pub pull_impulse: f32,
+    // impulse added along the player's swing while the rope is taut
+    #[serde(default = "lasso_default_swing_boost")]
+    pub swing_boost: f32,
+    // impulse pulling a lassoed player towards the thrower
+    #[serde(default = "lasso_default_yank_impulse")]
+    pub yank_impulse: f32,
+    // fraction of a lassoed player's velocity handed over to the thrower
+    #[serde(default = "lasso_default_momentum_steal")]
+    pub momentum_steal: f32,
+    // ticks a lassoed player needs to cut the rope
+    #[serde(default = "lasso_default_cut_ticks")]
+    pub cut_ticks: u16,
+    // speed added to a flung prop in the direction the player is looking
+    #[serde(default = "lasso_default_fling_speed")]
+    pub fling_speed: f32,
+    // ticks a flung prop can hurt players for
+    #[serde(default = "lasso_default_fling_ticks")]
+    pub fling_ticks: u16,
+    // slowest impact that still deals damage
+    #[serde(default = "lasso_default_fling_min_speed")]
+    pub fling_min_speed: f32,
+    #[serde(default = "lasso_default_fling_damage")]
+    pub fling_damage: u8,
+}
+
+/**
+ * Collision damage, impact severity is the change in a player's speed from a single tick of contact
+ */
+#[derive(Deserialize, Clone)]
+pub struct ImpactConfig {
+    // weakest impact clients are told about
+    #[serde(default = "impact_default_feedback_speed")]
+    pub feedback_speed: f32,
+    // weakest impact that deals damage
+    #[serde(default = "impact_default_damage_speed")]
+    pub damage_speed: f32,
+    // extra severity needed for each point of damage past the first
+    #[serde(default = "impact_default_speed_per_damage")]
+    pub speed_per_damage: f32,
+    // ticks the last player to shoot or lasso someone gets credit for their crashes
+    #[serde(default = "impact_default_attribution_ticks")]
+    pub attribution_ticks: u16,
+    // ticks after an impact before a player can be hurt by another one
+    #[serde(default = "impact_default_cooldown_ticks")]
+    pub cooldown_ticks: u16,
+}
+
+/**
+ * Thruster fuel, amounts are fractions of a full tank
+ */
+#[derive(Deserialize, Clone)]
+pub struct FuelConfig {
+    #[serde(default = "fuel_default_enabled")]
+    pub enabled: bool,
+    // fuel burned each tick for every direction a player thrusts in
+    #[serde(default = "fuel_default_drain_per_tick")]
+    pub drain_per_tick: f32,
+    #[serde(default = "fuel_default_regen_per_tick")]
+    pub regen_per_tick: f32,
+    // ticks without thrusting before fuel regenerates
+    #[serde(default = "fuel_default_regen_delay_ticks")]
+    pub regen_delay_ticks: u16,
+}
+
+/**
+ * One-shot dash in the direction a player is thrusting
+ */
+#[derive(Deserialize, Clone)]
+pub struct DashConfig {
+    #[serde(default = "dash_default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "dash_default_impulse")]
+    pub impulse: f32,
+    #[serde(default = "dash_default_cooldown_ticks")]
+    pub cooldown_ticks: u16,
+    // fraction of a full fuel tank each dash burns, ignored when fuel is disabled
+    #[serde(default = "dash_default_fuel_cost")]
+    pub fuel_cost: f32,
+}
+
+/**
+ * Inertial dampeners, speeds are in units per second
+ */
+#[derive(Deserialize, Clone)]
+pub struct BrakeConfig {
+    #[serde(default = "brake_default_enabled")]
+    pub enabled: bool,
+    // fraction of a player's speed the brake removes each tick
+    #[serde(default = "brake_default_damping")]
+    pub damping: f32,
+    // most speed the brake can remove in one tick
+    #[serde(default = "brake_default_max_decel")]
+    pub max_decel: f32,
+    // fraction of a full fuel tank braking burns each tick, ignored when fuel is disabled
+    #[serde(default = "brake_default_fuel_per_tick")]
+    pub fuel_per_tick: f32,
+    // gently damp players who aren't thrusting or braking, free of fuel
+    #[serde(default = "brake_default_auto_stabilize")]
+    pub auto_stabilize: bool,
+    #[serde(default = "brake_default_stabilize_damping")]
+    pub stabilize_damping: f32,
+}
+
+/**
+ * Damage dealt by a shot, scaled by the region of the player it hits
+ */
+#[derive(Deserialize, Clone)]
+pub struct WeaponConfig {
+    #[serde(default = "weapon_default_damage")]
+    pub damage: u8,
+    #[serde(default = "weapon_default_body_multiplier")]
+    pub body_multiplier: f32,
+    #[serde(default = "weapon_default_head_multiplier")]
+    pub head_multiplier: f32,
+}
+
+impl WeaponConfig {
+    pub fn damage_for(&self, region: HitRegion) -> u8 {
+        let multiplier = match region {
+            HitRegion::Body => self.body_multiplier,
+            HitRegion::Head => self.head_multiplier,
+        };
+        (self.damage as f32 * multiplier).round() as u8
+    }
+}
+
+/**
+ * Spawn selection and spawn protection
+ */
+#[derive(Deserialize, Clone)]
+pub struct SpawnConfig {
+    // ticks a freshly spawned player can't be damaged, firing ends it early
+    #[serde(default = "spawn_default_protection_ticks")]
+    pub protection_ticks: u16,
+    // spawns an opponent can see are scored as if they were this much closer to them
+    #[serde(default = "spawn_default_visible_penalty")]
+    pub visible_penalty: f32,
+}
+
+/**
+ * Server controlled players that fill up the lobby
+ */
+#[derive(Deserialize, Clone)]
+pub struct BotConfig {
+    // bots join until the lobby has this many players, 0 disables bots
+    #[serde(default = "bots_default_fill_to")]
+    pub fill_to: u8,
+    // from 0 to 1, how quickly and accurately bots aim
+    #[serde(default = "bots_default_skill")]
+    pub skill: f32,
+    // ticks before a bot shoots at an opponent it just spotted
+    #[serde(default = "bots_default_reaction_ticks")]
+    pub reaction_ticks: u16,
+    #[serde(default = "bots_default_view_dist")]
+    pub view_dist: f32,
+    #[serde(default = "bots_default_cruise_speed")]
+    pub cruise_speed: f32,
+}
+
+/**
+ * Capture the flag rules, distances are in world units
+ */
+#[derive(Deserialize, Clone)]
+pub struct CtfConfig {
+    // captures a team needs to win the round
+    #[serde(default = "ctf_default_capture_limit")]
+    pub capture_limit: u16,
+    // how close a player has to get to a flag to take or return it
+    #[serde(default = "ctf_default_pickup_radius")]
+    pub pickup_radius: f32,
+    // how close to their base a player has to bring the enemy flag to score
+    #[serde(default = "ctf_default_capture_radius")]
+    pub capture_radius: f32,
+    // a flag dragged further than this from its base no longer counts as home
+    #[serde(default = "ctf_default_home_radius")]
+    pub home_radius: f32,
+    // ticks a dropped flag lies around before returning to its base
+    #[serde(default = "ctf_default_return_ticks")]
+    pub return_ticks: u16,
+}
+
+/**
+ * King of the hill rules, hills themselves are placed in world/hills.json
+ */
+#[derive(Deserialize, Clone)]
+pub struct KothConfig {
+    // play in teams instead of every player for themselves
+    #[serde(default = "koth_default_teams")]
+    pub teams: bool,
+    // ticks an uncontested side needs to capture a hill, or to wear down someone else's hold
+    #[serde(default = "koth_default_capture_ticks")]
+    pub capture_ticks: u16,
+    // ticks before the next hill takes over
+    #[serde(default = "koth_default_rotate_ticks")]
+    pub rotate_ticks: u32,
+    // seconds of holding a hill needed to win the round
+    #[serde(default = "koth_default_score_limit")]
+    pub score_limit: u16,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum StatsFormat {
+    Json,
+    Csv,
+    Both,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct StatsConfig {
+    // write every player's stats to a file when a round ends
+    #[serde(default = "stats_default_enabled")]
+    pub enabled: bool,
+    // directory the files go in, relative to where the server runs
+    #[serde(default = "stats_default_dir")]
+    pub dir: String,
+    #[serde(default = "stats_default_format")]
+    pub format: StatsFormat,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct HistoryConfig {
+    // keep lifetime stats and ratings of every player by name
+    #[serde(default = "history_default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "history_default_path")]
+    pub path: String,
+    // rating a new player starts with
+    #[serde(default = "history_default_start_rating")]
+    pub start_rating: f32,
+    // most rating a player can gain or lose in one match
+    #[serde(default = "history_default_k_factor")]
+    pub k_factor: f32,
+    // players listed on the lobby leaderboard
+    #[serde(default = "history_default_leaderboard_size")]
+    pub leaderboard_size: usize,
+}
+
+/**
+ * What props with health break into
+ */
+#[derive(Deserialize, Clone)]
+pub struct FragmentConfig {
+    #[serde(default = "fragments_default_count")]
+    pub count: u8,
+    #[serde(default = "fragments_default_model")]
+    pub model: String,
+    // mesh of the model, used to size fragments
+    #[serde(default = "fragments_default_shape")]
+    pub shape: String,
+    // fragment size as a fraction of the broken prop's
+    #[serde(default = "fragments_default_size")]
+    pub size: f32,
+    // speed fragments fly apart at, on top of the prop's own velocity
+    #[serde(default = "fragments_default_speed")]
+    pub speed: f32,
+    // ticks before a fragment disappears
+    #[serde(default = "fragments_default_lifetime")]
+    pub lifetime: u16,
+}
+
+// what happens to a prop that leaves the map bound
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum PropBoundsPolicy {
+    // put back where the world file placed it, props spawned mid-round are removed instead
+    Respawn,
+    Despawn,
+    Ignore,
+}
+
+// what happens to a player that leaves the map bound
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum PlayerBoundsPolicy {
+    // living players die, credited to whoever last hit them, and dead bodies are stopped
+    Kill,
+    // living players and dead bodies are moved to a spawn point
+    Teleport,
+    Ignore,
+}
+
+/**
+ * Sphere outside the border that nothing should get past, and what to do with whatever does
+ */
+#[derive(Deserialize, Clone)]
+pub struct BoundsConfig {
+    #[serde(default = "bounds_default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "bounds_default_center")]
+    pub center: [f32; 3],
+    #[serde(default = "bounds_default_radius")]
+    pub radius: f32,
+    #[serde(default = "bounds_default_props")]
+    pub props: PropBoundsPolicy,
+    #[serde(default = "bounds_default_players")]
+    pub players: PlayerBoundsPolicy,
+}
+
+fn config_default_game_mode() -> GameModeKind { GameModeKind::Elimination }
+fn config_default_frag_limit() -> u16 { 10 }
+fn config_default_respawn_ticks() -> u16 { 180 } // about 3 seconds
+fn config_default_num_teams() -> u8 { 2 }
+fn config_default_friendly_fire() -> bool { false }
+fn config_default_auto_balance() -> bool { true }
+fn config_default_rounds_per_match() -> u8 { 1 }
+fn config_default_assist_ticks() -> u16 { 600 } // about 10 seconds
+fn config_default_zone() -> ZoneConfig {
+    ZoneConfig {
+        enabled: zone_default_enabled(),
+        center: zone_default_center(),
+        start_radius: zone_default_start_radius(),
+        stages: zone_default_stages(),
+        damage_ticks: zone_default_damage_ticks(),
+        push: zone_default_push(),
+    }
+}
+
+fn config_default_lasso() -> LassoConfig {
+    LassoConfig {
+        fire_vel: lasso_default_fire_vel(),
+        max_dist: lasso_default_max_dist(),
+        slack: lasso_default_slack(),
+        min_length: lasso_default_min_length(),
+        max_length: lasso_default_max_length(),
+        reel_in_speed: lasso_default_reel_in_speed(),
+        reel_out_speed: lasso_default_reel_out_speed(),
+        pull_impulse: lasso_default_pull_impulse(),
+        swing_boost: lasso_default_swing_boost(),
+        yank_impulse: lasso_default_yank_impulse(),
+        momentum_steal: lasso_default_momentum_steal(),
+        cut_ticks: lasso_default_cut_ticks(),
+        fling_speed: lasso_default_fling_speed(),
+        fling_ticks: lasso_default_fling_ticks(),
+        fling_min_speed: lasso_default_fling_min_speed(),
+        fling_damage: lasso_default_fling_damage(),
+    }
+}
+
+fn config_default_impact() -> ImpactConfig {
+    ImpactConfig {
+        feedback_speed: impact_default_feedback_speed(),
+        damage_speed: impact_default_damage_speed(),
+        speed_per_damage: impact_default_speed_per_damage(),
+        attribution_ticks: impact_default_attribution_ticks(),
+        cooldown_ticks: impact_default_cooldown_ticks(),
+    }
+}
+
+fn config_default_fuel() -> FuelConfig {
+    FuelConfig {
+        enabled: fuel_default_enabled(),
+        drain_per_tick: fuel_default_drain_per_tick(),
+        regen_per_tick: fuel_default_regen_per_tick(),
+        regen_delay_ticks: fuel_default_regen_delay_ticks(),
+    }
+}
+
+fn config_default_dash() -> DashConfig {
+    DashConfig {
+        enabled: dash_default_enabled(),
+        impulse: dash_default_impulse(),
+        cooldown_ticks: dash_default_cooldown_ticks(),
+        fuel_cost: dash_default_fuel_cost(),
+    }
+}
+
+fn config_default_brake() -> BrakeConfig {
+    BrakeConfig {
+        enabled: brake_default_enabled(),
+        damping: brake_default_damping(),
+        max_decel: brake_default_max_decel(),
+        fuel_per_tick: brake_default_fuel_per_tick(),
+        auto_stabilize: brake_default_auto_stabilize(),
+        stabilize_damping: brake_default_stabilize_damping(),
+    }
+}
+
+fn config_default_weapon() -> WeaponConfig {
+    WeaponConfig {
+        damage: weapon_default_damage(),
+        body_multiplier: weapon_default_body_multiplier(),
+        head_multiplier: weapon_default_head_multiplier(),
+    }
+}
+
+fn config_default_spawn() -> SpawnConfig {
+    SpawnConfig {
+        protection_ticks: spawn_default_protection_ticks(),
+        visible_penalty: spawn_default_visible_penalty(),
+    }
+}
+
+fn config_default_bots() -> BotConfig {
+    BotConfig {
+        fill_to: bots_default_fill_to(),
+        skill: bots_default_skill(),
+        reaction_ticks: bots_default_reaction_ticks(),
+        view_dist: bots_default_view_dist(),
+        cruise_speed: bots_default_cruise_speed(),
+    }
+}
+
+fn config_default_ctf() -> CtfConfig {
+    CtfConfig {
+        capture_limit: ctf_default_capture_limit(),
+        pickup_radius: ctf_default_pickup_radius(),
+        capture_radius: ctf_default_capture_radius(),
+        home_radius: ctf_default_home_radius(),
+        return_ticks: ctf_default_return_ticks(),
+    }
+}
+
+fn config_default_koth() -> KothConfig {
+    KothConfig {
+        teams: koth_default_teams(),
+        capture_ticks: koth_default_capture_ticks(),
+        rotate_ticks: koth_default_rotate_ticks(),
+        score_limit: koth_default_score_limit(),
+    }
+}
+
+fn config_default_stats() -> StatsConfig {
+    StatsConfig {
+        enabled: stats_default_enabled(),
+        dir: stats_default_dir(),
+        format: stats_default_format(),
+    }
+}
+
+fn config_default_history() -> HistoryConfig {
+    HistoryConfig {
+        enabled: history_default_enabled(),
+        path: history_default_path(),
+        start_rating: history_default_start_rating(),
+        k_factor: history_default_k_factor(),
+        leaderboard_size: history_default_leaderboard_size(),
+    }
+}
+
+fn config_default_fragments() -> FragmentConfig {
+    FragmentConfig {
+        count: fragments_default_count(),
+        model: fragments_default_model(),
+        shape: fragments_default_shape(),
+        size: fragments_default_size(),
+        speed: fragments_default_speed(),
+        lifetime: fragments_default_lifetime(),
+    }
+}
+
+fn config_default_bounds() -> BoundsConfig {
+    BoundsConfig {
+        enabled: bounds_default_enabled(),
+        center: bounds_default_center(),
+        radius: bounds_default_radius(),
+        props: bounds_default_props(),
+        players: bounds_default_players(),
+    }
+}
+
+fn zone_default_enabled() -> bool { false }
+fn zone_default_center() -> [f32; 3] { [0.0, 0.0, 0.0] }
+fn zone_default_start_radius() -> f32 { 250.0 }
+// about 1 minute before the first shrink, then 30 seconds between the others
+fn zone_default_stages() -> Vec<ZoneStage> {
+    vec![
+        ZoneStage { delay_ticks: 3750, shrink_ticks: 1250, radius: 160.0 },
+        ZoneStage { delay_ticks: 1875, shrink_ticks: 1250, radius: 90.0 },
+        ZoneStage { delay_ticks: 1875, shrink_ticks: 1250, radius: 40.0 },
+    ]
+}
+fn zone_default_damage_ticks() -> u16 { 120 } // about 2 seconds
+fn zone_default_push() -> f32 { 0.05 }
+
+fn lasso_default_fire_vel() -> f32 { 150.0 }
+fn lasso_default_max_dist() -> f32 { 500.0 }
+fn lasso_default_slack() -> f32 { 0.5 }
+fn lasso_default_min_length() -> f32 { 2.0 }
+fn lasso_default_max_length() -> f32 { 150.0 }
+fn lasso_default_reel_in_speed() -> f32 { 0.4 }
+fn lasso_default_reel_out_speed() -> f32 { 0.4 }
+fn lasso_default_pull_impulse() -> f32 { 0.1 }
+fn lasso_default_swing_boost() -> f32 { 0.02 }
+fn lasso_default_yank_impulse() -> f32 { 4.0 }
+fn lasso_default_momentum_steal() -> f32 { 0.5 }
+fn lasso_default_cut_ticks() -> u16 { 90 } // about 1.5 seconds
+fn lasso_default_fling_speed() -> f32 { 30.0 }
+fn lasso_default_fling_ticks() -> u16 { 180 } // about 3 seconds
+fn lasso_default_fling_min_speed() -> f32 { 15.0 }
+fn lasso_default_fling_damage() -> u8 { 1 }
+
+fn impact_default_feedback_speed() -> f32 { 8.0 }
+fn impact_default_damage_speed() -> f32 { 20.0 }
+fn impact_default_speed_per_damage() -> f32 { 15.0 }
+fn impact_default_attribution_ticks() -> u16 { 300 } // about 5 seconds
+fn impact_default_cooldown_ticks() -> u16 { 30 }
+
+fn fuel_default_enabled() -> bool { true }
+fn fuel_default_drain_per_tick() -> f32 { 0.004 } // about 4 seconds of thrusting in one direction
+fn fuel_default_regen_per_tick() -> f32 { 0.005 }
+fn fuel_default_regen_delay_ticks() -> u16 { 60 }
+
+fn dash_default_enabled() -> bool { true }
+fn dash_default_impulse() -> f32 { 2.0 }
+fn dash_default_cooldown_ticks() -> u16 { 180 }
+fn dash_default_fuel_cost() -> f32 { 0.2 }
+
+fn brake_default_enabled() -> bool { true }
+fn brake_default_damping() -> f32 { 0.05 }
+fn brake_default_max_decel() -> f32 { 0.5 }
+fn brake_default_fuel_per_tick() -> f32 { 0.003 }
+fn brake_default_auto_stabilize() -> bool { false }
+fn brake_default_stabilize_damping() -> f32 { 0.01 }
+
+fn weapon_default_damage() -> u8 { 1 }
+fn weapon_default_body_multiplier() -> f32 { 1.0 }
+fn weapon_default_head_multiplier() -> f32 { 2.0 }
+
+fn spawn_default_protection_ticks() -> u16 { 180 }
+fn spawn_default_visible_penalty() -> f32 { 0.25 }
+
+fn bots_default_fill_to() -> u8 { 0 }
+fn bots_default_skill() -> f32 { 0.5 }
+fn bots_default_reaction_ticks() -> u16 { 30 }
+fn bots_default_view_dist() -> f32 { 300.0 }
+fn bots_default_cruise_speed() -> f32 { 10.0 }
+
+fn ctf_default_capture_limit() -> u16 { 3 }
+fn ctf_default_pickup_radius() -> f32 { 3.0 }
+fn ctf_default_capture_radius() -> f32 { 6.0 }
+fn ctf_default_home_radius() -> f32 { 4.0 }
+fn ctf_default_return_ticks() -> u16 { 1800 } // about 30 seconds
+
+fn koth_default_teams() -> bool { false }
+fn koth_default_capture_ticks() -> u16 { 180 } // about 3 seconds
+fn koth_default_rotate_ticks() -> u32 { 3600 } // about a minute
+fn koth_default_score_limit() -> u16 { 100 }
+
+fn stats_default_enabled() -> bool { true }
+fn stats_default_dir() -> String { "stats".to_string() }
+fn stats_default_format() -> StatsFormat { StatsFormat::Json }
+
+fn history_default_enabled() -> bool { true }
+fn history_default_path() -> String { "history.json".to_string() }
+fn history_default_start_rating() -> f32 { 1000.0 }
+fn history_default_k_factor() -> f32 { 32.0 }
+fn history_default_leaderboard_size() -> usize { 10 }
+
+fn fragments_default_count() -> u8 { 4 }
+fn fragments_default_model() -> String { "rough_asteroid_small".to_string() }
+fn fragments_default_shape() -> String { "../client/resources/models/asteroids/rough_asteroid_small.obj".to_string() }
+fn fragments_default_size() -> f32 { 0.4 }
+fn fragments_default_speed() -> f32 { 5.0 }
+fn fragments_default_lifetime() -> u16 { 900 } // about 15 seconds
+
+fn bounds_default_enabled() -> bool { true }
+fn bounds_default_center() -> [f32; 3] { [0.0, 0.0, 0.0] }
+fn bounds_default_radius() -> f32 { 300.0 } // 50 metres past the border
+fn bounds_default_props() -> PropBoundsPolicy { PropBoundsPolicy::Respawn }
+fn bounds_default_players() -> PlayerBoundsPolicy { PlayerBoundsPolicy::Kill }
+
+pub fn load_server_config(path: &str) -> ServerConfig {
+    let j = fs::read_to_string(path).expect("Error reading server config file");
+    serde_json::from_str(&j).expect("Error deserializing server config file")
+}
@@ -0,0 +1,84 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::server_config::{StatsConfig, StatsFormat};
+
+// one player's line in a round's stats file
+#[derive(Serialize)]
+pub struct PlayerStats {
+    pub name: String,
+    pub bot: bool,
+    pub team: Option<u8>,
+    pub placement: u8,
+    pub kills: u16,
+    pub deaths: u16,
+    pub assists: u16,
+    pub shots: u16,
+    pub hits: u16,
+    pub accuracy: f32,
+    pub lasso_throws: u16,
+    pub lasso_attaches: u16,
+    // metres travelled while alive
+    pub distance: f32,
+    // seconds spent alive
+    pub time_alive: f32,
+    // metres per second
+    pub top_speed: f32,
+}
+
+#[derive(Serialize)]
+pub struct RoundStatsFile {
+    pub game_mode: String,
+    pub round: u8,
+    // seconds from the start of the round until it was decided
+    pub duration: f32,
+    pub players: Vec<PlayerStats>,
+}
+
+/**
+ * Write a round's stats into the configured directory, named after the time the round ended.
+ * Failures are only logged, a missing stats file shouldn't take the server down
+ *
+ * @param   config: where and in which format to write
+ * @param   stats: the round's stats
+ */
+pub fn write_stats(config: &StatsConfig, stats: &RoundStatsFile) {
+    if let Err(e) = fs::create_dir_all(&config.dir) {
+        eprintln!("Error creating stats directory \"{}\": {:?}", config.dir, e);
+        return;
+    }
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+    let path = Path::new(&config.dir).join(format!("round-{}-{}", timestamp, stats.round));
+
+    if config.format == StatsFormat::Json || config.format == StatsFormat::Both {
+        let j = serde_json::to_string_pretty(stats).expect("Stats serialization error");
+        write_file(&path.with_extension("json"), &j);
+    }
+    if config.format == StatsFormat::Csv || config.format == StatsFormat::Both {
+        write_file(&path.with_extension("csv"), &to_csv(stats));
+    }
+}
+
+fn write_file(path: &Path, contents: &str) {
+    match fs::write(path, contents) {
+        Ok(_) => println!("[SERVER]: Wrote round stats to {}", path.display()),
+        Err(e) => eprintln!("Error writing stats file \"{}\": {:?}", path.display(), e),
+    }
+}
+
+/**
+ * One row per player, the round's details are repeated on every row so files can be concatenated
+ */
+fn to_csv(stats: &RoundStatsFile) -> String {
+    let mut csv = "game_mode,round,duration,name,bot,team,placement,kills,deaths,assists,shots,hits,accuracy,lasso_throws,lasso_attaches,distance,time_alive,top_speed\n".to_string();
+    for player in &stats.players {
+        let team = player.team.map_or(String::new(), |team| team.to_string());
+        csv += &format!("{},{},{:.3},\"{}\",{},{},{},{},{},{},{},{},{:.3},{},{},{:.3},{:.3},{:.3}\n",
+            stats.game_mode, stats.round, stats.duration, player.name.replace('"', "\"\""), player.bot, team,
+            player.placement, player.kills, player.deaths, player.assists, player.shots, player.hits, player.accuracy,
+            player.lasso_throws, player.lasso_attaches, player.distance, player.time_alive, player.top_speed);
+    }
+    csv
+}
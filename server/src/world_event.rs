@@ -0,0 +1,154 @@
+use nalgebra::{UnitQuaternion, Vector3};
+use rand::{thread_rng, Rng};
+use rapier3d::prelude::*;
+use slotmap::DefaultKey;
+
+use shared::shared_components::*;
+use crate::ecs::{ECS, EVENT_LIFETIME};
+use crate::init_world::{init_world_events, model_radius};
+use crate::server_components::*;
+
+type Entity = DefaultKey;
+
+/**
+ * World events from the world file come up on a schedule during the round, each one that
+ * passes its chance roll is announced to every client as it starts
+ */
+impl ECS {
+    /**
+     * Load the world events and start their schedule over for a new round
+     */
+    pub fn schedule_world_events(&mut self) {
+        init_world_events(&mut self.world_events);
+        self.world_event_ticks = 0;
+        self.nebulae.clear();
+        self.supply_crates.clear();
+    }
+
+    /**
+     * Start whatever world events are due, clear nebulae that ran out and hand out supplies
+     */
+    pub fn update_world_events(&mut self) {
+        self.world_event_ticks += 1;
+        let tick = self.world_event_ticks;
+        let mut rng = thread_rng();
+        let mut started: Vec<(WorldEventSpec, Vector3<f32>)> = vec![];
+        for scheduled in &mut self.world_events {
+            if scheduled.next_tick.map_or(true, |next| next > tick) {
+                continue;
+            }
+            scheduled.next_tick = scheduled.interval.map(|interval| tick + interval);
+            // nothing new starts once the round is decided
+            if !self.round_over && rng.gen::<f32>() < scheduled.chance {
+                started.push((scheduled.event.clone(), scheduled.center + random_offset(scheduled.spread)));
+            }
+        }
+        for (event, pos) in started {
+            self.start_world_event(&event, pos);
+        }
+
+        for nebula in &mut self.nebulae {
+            nebula.ticks = nebula.ticks.saturating_sub(1);
+        }
+        self.nebulae.retain(|nebula| nebula.ticks > 0);
+
+        self.open_supply_crates();
+    }
+
+    /**
+     * Check if a point is hidden inside a nebula
+     */
+    pub fn in_nebula(&self, pos: &Vector3<f32>) -> bool {
+        self.nebulae.iter().any(|nebula| (pos - Vector3::new(nebula.cloud.x, nebula.cloud.y, nebula.cloud.z)).norm() < nebula.cloud.radius)
+    }
+
+    /**
+     * Set a world event off around pos and announce it
+     */
+    fn start_world_event(&mut self, event: &WorldEventSpec, pos: Vector3<f32>) {
+        let mut rng = thread_rng();
+        match *event {
+            WorldEventSpec::MeteorShower { count, speed, size, distance, lifetime } => {
+                // meteors share the fragment model
+                let shape = self.config.fragments.shape.clone();
+                let model_radius = *self.model_radii.entry(shape.clone()).or_insert_with(|| model_radius(&shape));
+                let scale = if model_radius > 0.0 { size / model_radius } else { 1.0 };
+                // they all come in from roughly the same side of the sky
+                let from = rng.gen::<UnitQuaternion<f32>>() * Vector3::z();
+                for _ in 0..count {
+                    let start = pos + (from + random_offset(0.3)).normalize() * distance;
+                    let target = pos + random_offset(distance * 0.2);
+                    let vel = (target - start).normalize() * speed;
+                    let rot = rng.gen::<UnitQuaternion<f32>>();
+                    let spin = rng.gen::<UnitQuaternion<f32>>() * Vector3::z() * rng.gen_range(0.0..2.0);
+                    let meteor = self.spawn_prop("meteor".to_string(), self.config.fragments.model.clone(), start.x, start.y, start.z,
+                        rot.i, rot.j, rot.k, rot.w, true, SharedShape::ball(size), scale, 1.0, 0.3, false, vel, spin);
+                    // fast enough to tunnel through players without continuous collision detection
+                    self.rigid_body_set[self.physics_components[meteor].handle].enable_ccd(true);
+                    self.fragment_timers.insert(meteor, lifetime);
+                }
+            },
+            WorldEventSpec::SupplyDrop { count, size } => {
+                for i in 0..count {
+                    // the first crate lands on the announced spot, the rest scatter around it
+                    let at = if i == 0 { pos } else { pos + random_offset(size * 20.0) };
+                    let rot = rng.gen::<UnitQuaternion<f32>>();
+                    let spin = rng.gen::<UnitQuaternion<f32>>() * Vector3::z() * 0.2;
+                    let supply_crate = self.spawn_prop("supply_crate".to_string(), "cube".to_string(), at.x, at.y, at.z,
+                        rot.i, rot.j, rot.k, rot.w, true, SharedShape::cuboid(size, size, size), size, 0.5, 0.3, false, Vector3::zeros(), spin);
+                    // players open crates by touching them, the capsule is about a metre across
+                    self.supply_crates.insert(supply_crate, size * 3.0_f32.sqrt() + 1.0);
+                }
+            },
+            WorldEventSpec::Nebula { radius, duration } => {
+                self.nebulae.push(Nebula {
+                    cloud: NebulaComponent { x: pos.x, y: pos.y, z: pos.z, radius },
+                    ticks: duration,
+                });
+            },
+        }
+
+        let event_key = self.name_components.insert("announcement_event".to_string());
+        self.events.push(event_key);
+        self.event_components.insert(event_key, EventComponent { lifetime: EVENT_LIFETIME, event_type: EventType::AnnouncementEvent { kind: event.kind(), x: pos.x, y: pos.y, z: pos.z } });
+    }
+
+    /**
+     * Restock the first living player touching each supply crate, using the crate up
+     */
+    fn open_supply_crates(&mut self) {
+        let mut opened: Vec<(Entity, Entity)> = vec![];
+        for (supply_crate, &reach) in &self.supply_crates {
+            let crate_pos = self.rigid_body_set[self.physics_components[supply_crate].handle].translation();
+            let player = self.players.iter().copied().find(|&player| {
+                self.player_health_components[player].alive
+                    && (self.rigid_body_set[self.physics_components[player].handle].translation() - crate_pos).norm() < reach
+            });
+            if let Some(player) = player {
+                opened.push((supply_crate, player));
+            }
+        }
+
+        for (supply_crate, player) in opened {
+            self.player_weapon_components[player] = PlayerWeaponComponent {
+                cooldown: self.player_weapon_components[player].cooldown,
+                ..PlayerWeaponComponent::default()
+            };
+            self.player_fuel_components[player] = PlayerFuelComponent::default();
+            self.player_health_components[player].health = PlayerHealthComponent::default().health;
+            self.remove_prop(supply_crate);
+
+            let event_key = self.name_components.insert("supply_pickup_event".to_string());
+            self.events.push(event_key);
+            self.event_components.insert(event_key, EventComponent { lifetime: EVENT_LIFETIME, event_type: EventType::SupplyPickupEvent { player } });
+        }
+    }
+}
+
+/**
+ * Random offset spread evenly through a ball of the given radius
+ */
+fn random_offset(radius: f32) -> Vector3<f32> {
+    let mut rng = thread_rng();
+    rng.gen::<UnitQuaternion<f32>>() * Vector3::z() * radius * rng.gen::<f32>().cbrt()
+}
@@ -10,7 +10,11 @@ pub mod shared_functions;
 pub const TICK_SPEED: u64 = 16;
 pub const MOVE_DELTA: f32 = 0.1;
 pub const MIN_PLAYERS: usize = 2;
+// the HUD and lobby have room for this many players
+pub const MAX_PLAYERS: usize = 4;
 pub const AMMO_COUNT: u8 = 6;
+// longest name a player can go by, extra characters are cut off
+pub const MAX_NAME_LENGTH: usize = 16;
 
 /** ===========================================================================
  * client settings
@@ -61,6 +65,9 @@ pub const HITS_3_PATH: &str = "resources/ui_textures/game_over/hits-3.png";
 pub const HITS_4_PATH: &str = "resources/ui_textures/game_over/hits-4.png";
 pub const HITS_5_PATH: &str = "resources/ui_textures/game_over/hits-5.png";
 pub const HITS_6_PATH: &str = "resources/ui_textures/game_over/hits-6.png";
+pub const HITS_7_PATH: &str = "resources/ui_textures/game_over/hits-7.png";
+pub const HITS_8_PATH: &str = "resources/ui_textures/game_over/hits-8.png";
+pub const HITS_9_PATH: &str = "resources/ui_textures/game_over/hits-9.png";
 
 pub const P1_TXT_PATH: &str = "resources/ui_textures/game_over/p1.png";
 pub const P2_TXT_PATH: &str = "resources/ui_textures/game_over/p2.png";
@@ -151,6 +158,7 @@ pub const GAME_OVER_TXT_PATH: &str = "resources/ui_textures/screen_text/game-ove
 
 pub const DAMAGE_PATH: &str = "resources/ui_textures/damage.png";
 pub const HITMARKER_PATH: &str = "resources/ui_textures/crosshair/hitmarker_2.png";
+pub const HEADSHOT_HITMARKER_PATH: &str = "resources/ui_textures/crosshair/hitmarker.png";
 pub const P1_KILLMARKER_PATH: &str = "resources/ui_textures/crosshair/killmarker_p1.png";
 pub const P2_KILLMARKER_PATH: &str = "resources/ui_textures/crosshair/killmarker_p2.png";
 pub const P3_KILLMARKER_PATH: &str = "resources/ui_textures/crosshair/killmarker_p3.png";
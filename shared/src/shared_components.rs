@@ -16,6 +16,11 @@ pub struct PlayerInputComponent {
     pub s_pressed: bool,
     pub d_pressed: bool,
     pub r_pressed: bool,
+    pub q_pressed: bool,
+    pub e_pressed: bool,
+    pub f_pressed: bool,
+    pub v_pressed: bool,
+    pub x_pressed: bool,
     pub shift_pressed: bool,
     pub ctrl_pressed: bool,
     pub enter_pressed: bool,
@@ -40,6 +45,11 @@ impl PlayerInputComponent {
             enter_pressed: false,
             reset_pressed: false,
             r_pressed: false,
+            q_pressed: false,
+            e_pressed: false,
+            f_pressed: false,
+            v_pressed: false,
+            x_pressed: false,
             camera_qx: 0.0,
             camera_qy: 0.0,
             camera_qz: 0.0,
@@ -55,17 +65,37 @@ pub struct ClientECS {
     pub name_components: SlotMap<Entity, String>,
     pub position_components: SecondaryMap<Entity, PositionComponent>,
     pub weapon_components: SecondaryMap<Entity, PlayerWeaponComponent>,
+    pub fuel_components: SecondaryMap<Entity, PlayerFuelComponent>,
+    pub dash_components: SecondaryMap<Entity, PlayerDashComponent>,
     pub model_components: SecondaryMap<Entity, ModelComponent>,
     pub health_components: SecondaryMap<Entity, PlayerHealthComponent>,
     pub particle_components: SecondaryMap<Entity, ParticleComponent>,
     pub player_lasso_components: SecondaryMap<Entity, PlayerLassoComponent>,
     pub velocity_components: SecondaryMap<Entity, VelocityComponent>,
     pub event_components: SecondaryMap<Entity, EventComponent>,
+    pub team_components: SecondaryMap<Entity, u8>,
+    // players whose inertial dampeners are slowing them this tick
+    pub dampening: SecondaryMap<Entity, bool>,
+    pub flag_components: SecondaryMap<Entity, FlagComponent>,
+    pub hills: Vec<HillComponent>,
+    pub force_sources: Vec<ForceSourceComponent>,
+    pub nebulae: Vec<NebulaComponent>,
     pub players: Vec<Entity>,
     pub ids: Vec<Entity>,
     pub renderables: Vec<Entity>,
     pub events: Vec<Entity>,
+    pub standings: Vec<Standing>,
+    pub match_scores: SecondaryMap<Entity, MatchScore>,
+    pub round_number: u8,
+    pub rounds_per_match: u8,
+    // ticks between dashes, for the HUD cooldown indicator
+    pub dash_cooldown_ticks: u16,
+    // ticks of spawn protection a fresh spawn gets, for the HUD shield indicator
+    pub spawn_protection_ticks: u16,
     pub active_players: u8,
+    pub round_over: bool,
+    pub match_over: bool,
+    pub zone: PlayZone,
     pub game_ended: bool
 }
 
@@ -75,17 +105,34 @@ impl ClientECS {
             name_components: SlotMap::new(),
             position_components: SecondaryMap::new(),
             weapon_components: SecondaryMap::new(),
+            fuel_components: SecondaryMap::new(),
+            dash_components: SecondaryMap::new(),
             model_components: SecondaryMap::new(),
             health_components: SecondaryMap::new(),
             particle_components: SecondaryMap::new(),
             player_lasso_components: SecondaryMap::new(),
             event_components: SecondaryMap::new(),
             velocity_components: SecondaryMap::new(),
+            team_components: SecondaryMap::new(),
+            dampening: SecondaryMap::new(),
+            flag_components: SecondaryMap::new(),
+            hills: vec![],
+            force_sources: vec![],
+            nebulae: vec![],
             players: vec![],
             ids: vec![],
             renderables: vec![],
             events: vec![],
+            standings: vec![],
+            match_scores: SecondaryMap::new(),
+            round_number: 0,
+            rounds_per_match: 1,
+            dash_cooldown_ticks: 1,
+            spawn_protection_ticks: 1,
             active_players: 0,
+            round_over: false,
+            match_over: false,
+            zone: PlayZone::default(),
             game_ended: false
         }
     }
@@ -96,9 +143,15 @@ pub struct LobbyECS {
     pub name_components: SlotMap<Entity, String>,
     pub position_components: SecondaryMap<Entity, PositionComponent>,
     pub ready_players: SecondaryMap<Entity, bool>,
+    pub team_components: SecondaryMap<Entity, u8>,
     pub players: Vec<Entity>,
     pub ids: Vec<Entity>,
     pub sky: usize,
+    pub num_teams: u8,
+    // highest rated players in the server's match history, best first
+    pub leaderboard: Vec<LeaderboardEntry>,
+    // lifetime stats of the players in the lobby who have played before
+    pub records: SecondaryMap<Entity, LeaderboardEntry>,
     pub start_game: bool
 }
 
@@ -108,17 +161,36 @@ impl LobbyECS {
             name_components: SlotMap::new(),
             position_components: SecondaryMap::new(),
             ready_players: SecondaryMap::new(),
+            team_components: SecondaryMap::new(),
             players: vec![],
             ids: vec![],
             sky: 0,
+            num_teams: 0,
+            leaderboard: vec![],
+            records: SecondaryMap::new(),
             start_game: false,
         }
     }
 }
 
+// a player's lifetime stats from the server's match history
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub rating: f32,
+    pub matches: u32,
+    pub win_rate: f32,
+    pub kills: u32,
+    pub deaths: u32
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ReadyECS {
-    pub ready: bool
+    pub ready: bool,
+    // requested team, only used when the server is running a team mode
+    pub team: Option<u8>,
+    // name the player goes by in the match history, sent once after connecting
+    pub name: Option<String>
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -170,11 +242,43 @@ impl PlayerWeaponComponent {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerFuelComponent {
+    // fraction of a full tank
+    pub fuel: f32,
+    // ticks until fuel starts regenerating
+    pub regen_cooldown: u16
+}
+
+impl PlayerFuelComponent {
+    pub fn default() -> PlayerFuelComponent {
+        PlayerFuelComponent {
+            fuel: 1.0,
+            regen_cooldown: 0
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerDashComponent {
+    // ticks until the player can dash again
+    pub cooldown: u16
+}
+
+impl PlayerDashComponent {
+    pub fn default() -> PlayerDashComponent {
+        PlayerDashComponent {
+            cooldown: 0
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PlayerHealthComponent {
     pub alive: bool,
     pub health: u8,
-    pub hits: u8
+    // ticks of spawn protection left, the player can't be damaged while above 0
+    pub shield_ticks: u16
 }
 
 impl PlayerHealthComponent {
@@ -182,7 +286,7 @@ impl PlayerHealthComponent {
         PlayerHealthComponent {
             alive : true,
             health : 2,
-            hits: 0
+            shield_ticks: 0
         }
     }
 }
@@ -191,7 +295,57 @@ impl PlayerHealthComponent {
 pub struct PlayerLassoComponent {
     pub anchor_x: f32,
     pub anchor_y: f32,
-    pub anchor_z: f32
+    pub anchor_z: f32,
+    // player the lasso is tied to, if any
+    pub tethered: Option<Entity>
+}
+
+// what kind of entity a lasso attached to
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum LassoTarget {
+    Prop,
+    Player,
+}
+
+// part of a player a shot landed on, props are always hit in the body
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum HitRegion {
+    Body,
+    Head,
+}
+
+// what dealt a point of damage, deaths are attributed to the last one
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum DamageSource {
+    Shot,
+    Headshot,
+    // a prop flung from a lasso
+    Prop,
+    // crashing into something too fast
+    Impact,
+    Zone,
+    // flying out past the map bound
+    OutOfBounds,
+}
+
+// where a capture-the-flag flag is
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum FlagState {
+    Home,
+    Carried,
+    Dropped,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FlagComponent {
+    pub team: u8,
+    pub state: FlagState,
+    // player holding the flag, only set while it is carried
+    pub carrier: Option<Entity>,
+    // the flag's base, where it returns to and where its team scores captures
+    pub home_x: f32,
+    pub home_y: f32,
+    pub home_z: f32,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -202,6 +356,7 @@ pub enum EventType {
     HitEvent {
         player: Entity,
         target: Entity,
+        region: HitRegion,
         hit_x: f32,
         hit_y: f32,
         hit_z: f32
@@ -210,7 +365,9 @@ pub enum EventType {
         player: Entity
     },
     LassoAttachEvent {
+        player: Entity,
         target: Entity,
+        target_type: LassoTarget,
         hit_x: f32,
         hit_y: f32,
         hit_z: f32
@@ -221,12 +378,18 @@ pub enum EventType {
     ReloadEvent {
         player: Entity,
     },
+    // killer is the player themselves for environmental deaths, cause says which
     DeathEvent {
         player: Entity,
-        killer: Entity
+        killer: Entity,
+        cause: DamageSource,
+        // other opponents who damaged the player shortly before they died
+        assists: Vec<Entity>,
     },
+    // killer is whoever last damaged the player before they left, if anyone
     DisconnectEvent {
-        player: Entity
+        player: Entity,
+        killer: Option<Entity>,
     },
     StartMoveEvent {
         player: Entity,
@@ -234,6 +397,69 @@ pub enum EventType {
     StopMoveEvent {
         player: Entity,
     },
+    // dir is the unit direction of the dash impulse
+    DashEvent {
+        player: Entity,
+        dir_x: f32,
+        dir_y: f32,
+        dir_z: f32
+    },
+    ZoneDamageEvent {
+        player: Entity,
+    },
+    // player crashed into something, magnitude is the change in their speed
+    ImpactEvent {
+        player: Entity,
+        x: f32,
+        y: f32,
+        z: f32,
+        magnitude: f32
+    },
+    // a prop flung by player struck target
+    PropHitEvent {
+        player: Entity,
+        target: Entity,
+        hit_x: f32,
+        hit_y: f32,
+        hit_z: f32
+    },
+    FlagTakenEvent {
+        flag: Entity,
+        player: Entity,
+    },
+    FlagDropEvent {
+        flag: Entity,
+        player: Entity,
+    },
+    // player is None when the flag went home on its own
+    FlagReturnEvent {
+        flag: Entity,
+        player: Option<Entity>,
+    },
+    // player brought the enemy flag to their base
+    FlagCaptureEvent {
+        flag: Entity,
+        player: Entity,
+    },
+    // player shot a prop to pieces, radius is roughly how big it was
+    BreakEvent {
+        player: Entity,
+        x: f32,
+        y: f32,
+        z: f32,
+        radius: f32,
+    },
+    // a scheduled world event just started, centered around x, y, z
+    AnnouncementEvent {
+        kind: WorldEventKind,
+        x: f32,
+        y: f32,
+        z: f32,
+    },
+    // player opened a supply crate and was restocked
+    SupplyPickupEvent {
+        player: Entity,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -259,6 +485,132 @@ impl VelocityComponent {
     }
 }
 
+// one row of the round leaderboard, ordered best first by the server's game mode
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Standing {
+    pub player: Entity,
+    pub score: i32,
+    pub kills: u16,
+    pub deaths: u16
+}
+
+// what a force source does to bodies inside it, strengths are accelerations in m/s^2
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum ForceKind {
+    // pulls towards the center, strongest there and fading out towards the edge
+    Well(f32),
+    // pushes away from the center, fading out the same way
+    Repulsor(f32),
+    // the same push everywhere inside, like solar wind
+    Wind(f32, f32, f32),
+}
+
+// a sphere of the world that pushes or pulls everything in it, defined in the world file
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ForceSourceComponent {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub radius: f32,
+    pub kind: ForceKind,
+}
+
+// world events the server schedules during a round, announced to every client when they start
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum WorldEventKind {
+    // fast asteroids fly in through the arena
+    MeteorShower,
+    // crates that restock whoever touches them
+    SupplyDrop,
+    // a cloud that hides everything inside it for a while
+    Nebula,
+}
+
+// a temporary low-visibility cloud from a nebula world event
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NebulaComponent {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub radius: f32,
+}
+
+// king of the hill capture zone, a sphere scoring for whoever holds it uncontested
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HillComponent {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub radius: f32,
+    // hills take turns being the one in play
+    pub active: bool,
+    // player capturing or holding the hill, shared with their team in team modes
+    pub owner: Option<Entity>,
+    // the owner holds the hill once this reaches 1
+    pub progress: f32,
+    // more than one side is standing in the hill
+    pub contested: bool,
+}
+
+// sphere players must stay inside, shrinks over the course of a round
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayZone {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub radius: f32
+}
+
+impl PlayZone {
+    pub fn default() -> PlayZone {
+        PlayZone {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            radius: 250.0,
+        }
+    }
+}
+
+// one player's end-of-round results, computed by the server
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoundResult {
+    pub player: Entity,
+    pub placement: u8,
+    pub kills: u16,
+    pub deaths: u16,
+    pub hits: u16,
+    pub shots: u16,
+    pub assists: u16,
+    pub accuracy: f32,
+    // seconds spent alive during the round
+    pub survival_time: f32
+}
+
+// server -> client message sent once when a round ends, ordered by placement
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoundResults {
+    pub results: Vec<RoundResult>
+}
+
+// a player's totals across every round of the current match
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct MatchScore {
+    pub wins: u8,
+    pub kills: u16,
+    pub hits: u16
+}
+
+impl MatchScore {
+    pub fn default() -> MatchScore {
+        MatchScore {
+            wins: 0,
+            kills: 0,
+            hits: 0,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ParticleComponent {
     pub x: f32,